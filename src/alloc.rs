@@ -0,0 +1,142 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, counting allocations and the bytes in use. The
+/// binary installs it with `#[global_allocator]`, without it there is no
+/// `Usage` to report.
+///
+/// The counters are process wide, so a `Measure` only describes one solver
+/// while nothing else runs, i.e. not with `--parallel` or after a solver was
+/// abandoned on a timeout. `runner::discard_shared_memory` drops those.
+pub struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(size: usize) {
+  let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+  PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc(layout);
+    if !ptr.is_null() {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+      grow(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    let ptr = System.alloc_zeroed(layout);
+    if !ptr.is_null() {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+      grow(layout.size());
+    }
+    ptr
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout);
+    CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    let new = System.realloc(ptr, layout, new_size);
+    if !new.is_null() {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+      CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+      grow(new_size);
+    }
+    new
+  }
+}
+
+/// Whether `Counting` is the global allocator, anything that runs has
+/// allocated by the time this is asked.
+pub fn enabled() -> bool {
+  ALLOCATIONS.load(Ordering::Relaxed) > 0
+}
+
+/// Heap use of one phase: the most bytes it had live on top of what was
+/// already allocated when it started, and how many (re)allocations it made.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+  pub peak: usize,
+  pub allocations: usize,
+}
+
+pub struct Measure {
+  start: usize,
+  allocations: usize,
+}
+
+impl Measure {
+  pub fn start() -> Measure {
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+
+    Measure {
+      start: current,
+      allocations: ALLOCATIONS.load(Ordering::Relaxed),
+    }
+  }
+
+  /// `None` when `Counting` is not the global allocator.
+  pub fn finish(&self) -> Option<Usage> {
+    enabled().then(|| Usage {
+      peak: PEAK.load(Ordering::Relaxed).saturating_sub(self.start),
+      allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+    })
+  }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+  let bytes = bytes as f64;
+
+  if bytes >= 1024.0 * 1024.0 {
+    format!("{:.2}MiB", bytes / (1024.0 * 1024.0))
+  } else if bytes >= 1024.0 {
+    format!("{:.2}KiB", bytes / 1024.0)
+  } else {
+    format!("{}B", bytes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_measure() {
+    // tests run on the system allocator, so only this test moves the live
+    // bytes and allocations. Other tests start measures too, which sets the
+    // peak back to the live bytes, so it is only checked while they are live.
+    let layout = Layout::from_size_align(1024, 8).unwrap();
+    let measure = Measure::start();
+
+    unsafe {
+      let ptr = Counting.alloc(layout);
+      let ptr = Counting.realloc(ptr, layout, 4096);
+      assert_eq!(
+        measure.finish(),
+        Some(Usage {
+          peak: 4096,
+          allocations: 2
+        })
+      );
+      Counting.dealloc(ptr, Layout::from_size_align(4096, 8).unwrap());
+    }
+
+    assert_eq!(measure.finish().map(|usage| usage.allocations), Some(2));
+  }
+
+  #[test]
+  fn test_format_bytes() {
+    assert_eq!(format_bytes(512), "512B");
+    assert_eq!(format_bytes(1536), "1.50KiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024), "3.00MiB");
+  }
+}
@@ -0,0 +1,103 @@
+//! The answer a part of a puzzle comes up with.
+
+use std::fmt;
+
+/// A puzzle answer. Numbers compare by value regardless of the variant they
+/// are stored in, so a day can switch from `u64` to `i64` without breaking
+/// its tests or the known answers.
+#[derive(Debug, Clone)]
+pub enum Answer {
+  /// A number that can be negative.
+  Signed(i64),
+  /// A non-negative number.
+  Unsigned(u64),
+  /// A number too large for `u64`.
+  Big(u128),
+  /// Anything that is not a number, e.g. letters drawn on a grid.
+  Text(String),
+}
+
+impl Answer {
+  /// Sign and magnitude of a numeric answer.
+  fn numeric(&self) -> Option<(bool, u128)> {
+    match self {
+      Answer::Signed(n) => Some((*n < 0, n.unsigned_abs() as u128)),
+      Answer::Unsigned(n) => Some((false, *n as u128)),
+      Answer::Big(n) => Some((false, *n)),
+      Answer::Text(_) => None,
+    }
+  }
+}
+
+impl PartialEq for Answer {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Answer::Text(a), Answer::Text(b)) => a == b,
+      (a, b) => a.numeric().is_some() && a.numeric() == b.numeric(),
+    }
+  }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Answer::Signed(n) => n.fmt(f),
+      Answer::Unsigned(n) => n.fmt(f),
+      Answer::Big(n) => n.fmt(f),
+      Answer::Text(s) => s.fmt(f),
+    }
+  }
+}
+
+macro_rules! from_number {
+  ($($variant:ident($target:ty) <- $($source:ty),*;)*) => {
+    $($(
+      impl From<$source> for Answer {
+        fn from(n: $source) -> Answer {
+          Answer::$variant(n as $target)
+        }
+      }
+    )*)*
+  };
+}
+
+from_number! {
+  Signed(i64) <- i32, i64;
+  Unsigned(u64) <- u32, u64, usize;
+  Big(u128) <- u128;
+}
+
+impl From<String> for Answer {
+  fn from(s: String) -> Answer {
+    Answer::Text(s)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(s: &str) -> Answer {
+    Answer::Text(s.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_eq() {
+    assert_eq!(Answer::Signed(42), Answer::Unsigned(42));
+    assert_eq!(Answer::Unsigned(42), Answer::Big(42));
+    assert_ne!(Answer::Signed(-42), Answer::Unsigned(42));
+    assert_ne!(Answer::Text("42".to_string()), Answer::Unsigned(42));
+    assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+  }
+
+  #[test]
+  fn test_display() {
+    assert_eq!(Answer::from(-3i64).to_string(), "-3");
+    assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+    assert_eq!(Answer::from("abc").to_string(), "abc");
+  }
+}
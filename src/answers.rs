@@ -0,0 +1,102 @@
+use std::fs;
+use std::io;
+
+use serde::Deserialize;
+
+use crate::day::Part;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// One confirmed answer. `input` is the `input::checksum` of the input it is
+/// for, without it the answer holds for any input of that day.
+#[derive(Debug, Deserialize)]
+struct KnownAnswer {
+  day: u8,
+  part: String,
+  value: String,
+  #[serde(default)]
+  input: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Answers {
+  #[serde(default, rename = "answer")]
+  answers: Vec<KnownAnswer>,
+}
+
+impl Answers {
+  /// Reads the known answers, a missing file just means nothing is known yet.
+  /// Any other file that cannot be read is an error, so `verify` does not
+  /// pass for lack of answers.
+  pub fn load(path: &str) -> Result<Answers, String> {
+    match fs::read_to_string(path) {
+      Ok(content) => Answers::parse(&content).map_err(|err| format!("{}: {}", path, err)),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+      Err(err) => Err(format!("{}: {}", path, err)),
+    }
+  }
+
+  fn parse(content: &str) -> Result<Answers, String> {
+    toml::from_str(content).map_err(|err| err.to_string())
+  }
+
+  /// The confirmed answer for the input with `checksum`, an answer recorded
+  /// for exactly that input wins over one without an `input` key.
+  pub fn get(&self, day: u8, part: Part, checksum: Option<&str>) -> Option<&str> {
+    let candidates = self
+      .answers
+      .iter()
+      .filter(|answer| answer.day == day && answer.part == part.to_string());
+
+    candidates
+      .clone()
+      .find(|answer| answer.input.is_some() && answer.input.as_deref() == checksum)
+      .or_else(|| candidates.clone().find(|answer| answer.input.is_none()))
+      .map(|answer| answer.value.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_get() {
+    let answers = Answers::parse(
+      "[[answer]]\nday = 5\npart = \"a\"\nvalue = \"35\"\n\n\
+       [[answer]]\nday = 5\npart = \"b\"\nvalue = \"46\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(answers.get(5, Part::A, None), Some("35"));
+    assert_eq!(answers.get(5, Part::B, Some("ab12")), Some("46"));
+    assert_eq!(answers.get(6, Part::A, None), None);
+  }
+
+  #[test]
+  fn test_get_by_input() {
+    let answers = Answers::parse(
+      "[[answer]]\nday = 6\npart = \"a\"\nvalue = \"288\"\ninput = \"ab12\"\n\n\
+       [[answer]]\nday = 6\npart = \"a\"\nvalue = \"1\"\n\n\
+       [[answer]]\nday = 6\npart = \"b\"\nvalue = \"71503\"\ninput = \"ab12\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(answers.get(6, Part::A, Some("ab12")), Some("288"));
+    assert_eq!(answers.get(6, Part::A, Some("cd34")), Some("1"));
+    assert_eq!(answers.get(6, Part::B, Some("cd34")), None);
+    assert_eq!(answers.get(6, Part::B, None), None);
+  }
+
+  #[test]
+  fn test_load() {
+    let missing = std::env::temp_dir().join(format!("answers_{}.toml", std::process::id()));
+    assert!(Answers::load(missing.to_str().unwrap())
+      .unwrap()
+      .answers
+      .is_empty());
+
+    // a directory exists but cannot be read as a file
+    assert!(Answers::load(std::env::temp_dir().to_str().unwrap()).is_err());
+  }
+}
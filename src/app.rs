@@ -0,0 +1,284 @@
+use std::path::Path;
+use std::time::Instant;
+
+use rayon::prelude::*;
+
+use crate::alloc::{self, Usage};
+use crate::answers::{Answers, ANSWERS_FILE};
+use crate::cli::Command;
+use crate::input::Source;
+use crate::report::{self, Format};
+use crate::watch::{self, Watched};
+use crate::{bench, cli, input, perf, registry, runner, scaffold, Error, Params, Part, Puzzle};
+
+fn usage(program: &str) -> ! {
+  eprintln!("Usage: {} {}", program, cli::USAGE);
+  eprintln!(
+    "\nInputs are read from {}, or from the directory in {}.",
+    input::input_dir().join("dayN.txt").display(),
+    input::INPUT_DIR_VAR
+  );
+  eprintln!("\nAvailable days:");
+  for puzzle in registry() {
+    let parts = puzzle
+      .parts()
+      .iter()
+      .map(|part| part.to_string())
+      .collect::<Vec<String>>()
+      .join(", ");
+    eprintln!("  {:>2}: {} ({})", puzzle.number(), puzzle.title(), parts);
+    for param in puzzle.params() {
+      eprintln!(
+        "        --param {:<20} {}",
+        param.to_string(),
+        param.description
+      );
+    }
+  }
+  std::process::exit(1);
+}
+
+/// The `aoc-rust-2023` command line, see `cli::USAGE`. The binary only
+/// installs `alloc::Counting` and calls this.
+pub fn main() {
+  let args: Vec<_> = std::env::args().collect();
+  let options = cli::parse(args[1..].to_vec()).unwrap_or_else(|err| {
+    eprintln!("{}\n", err);
+    usage(&args[0]);
+  });
+
+  if options.command == Command::New {
+    let day = options.days.parse().expect("cli checks the day");
+    match scaffold::create(Path::new("src"), day) {
+      Ok(paths) => {
+        for path in paths {
+          println!("Wrote {}", path.display());
+        }
+      }
+      Err(err) => {
+        eprintln!("{}", err);
+        std::process::exit(1);
+      }
+    }
+    return;
+  }
+
+  if options.command == Command::Compare {
+    let history = perf::load(Path::new(perf::HISTORY_FILE)).unwrap_or_else(|err| {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    });
+
+    match perf::compare(&history, options.baseline.as_deref()) {
+      Ok((baseline, current, comparisons)) => {
+        let threshold = options.threshold.unwrap_or(perf::DEFAULT_THRESHOLD);
+        if !perf::print_table(&baseline, &current, &comparisons, threshold) {
+          std::process::exit(1);
+        }
+      }
+      Err(err) => {
+        eprintln!("{}", err);
+        std::process::exit(1);
+      }
+    }
+    return;
+  }
+
+  let puzzles = runner::select_days(&options.days, &registry()).unwrap_or_else(|err| {
+    eprintln!("{}\n", err);
+    usage(&args[0]);
+  });
+
+  if let Some((name, _)) = options.params.iter().find(|(name, _)| {
+    !puzzles
+      .iter()
+      .any(|puzzle| puzzle.params().iter().any(|param| param.name == name))
+  }) {
+    eprintln!("No selected day has a parameter named {}\n", name);
+    usage(&args[0]);
+  }
+
+  if let Some(threads) = options.threads {
+    rayon::ThreadPoolBuilder::new()
+      .num_threads(threads)
+      .build_global()
+      .expect("the global pool is only built once");
+  }
+
+  let answers = Answers::load(ANSWERS_FILE).unwrap_or_else(|err| {
+    eprintln!("{}", err);
+    std::process::exit(1);
+  });
+
+  let verify = options.command == Command::Verify;
+  let parts = match options.part {
+    Some(part) => vec![part],
+    None => vec![Part::A, Part::B],
+  };
+
+  runner::quiet_solver_panics();
+
+  if let (true, [puzzle]) = (options.watch, &puzzles[..]) {
+    let day = puzzle.number();
+    let params = Params::new(day, puzzle.params(), &options.params);
+    let input = match &options.input {
+      Source::File(path) => path.clone(),
+      _ => input::day_path(day),
+    };
+    let mut files = vec![Watched::new("input", input)];
+    // only days made with `new` keep their example in a file, the older ones
+    // have it inline in their tests
+    let example = input::example_path(day);
+    if example.exists() {
+      files.push(Watched::new("example", example));
+    }
+
+    println!("Day {}: {}, watching for changes", day, puzzle.title());
+    watch::watch(day, &mut files, |input| {
+      runner::run(*puzzle, &parts, input, &params, &answers, options.timeout)
+    });
+  }
+
+  let read_input = |puzzle: &dyn Puzzle| match options.input.read(puzzle.number()) {
+    Ok(input) => Some(input),
+    Err(err) if puzzles.len() == 1 => {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    }
+    Err(_) => None,
+  };
+
+  if let Some(iterations) = options.bench {
+    let mut benches = vec![];
+    let mut bench_failed = false;
+    for puzzle in &puzzles {
+      let Some(input) = read_input(*puzzle) else {
+        eprintln!("Day {}: no input, skipped", puzzle.number());
+        continue;
+      };
+
+      let params = Params::new(puzzle.number(), puzzle.params(), &options.params);
+      for part in parts.iter().filter(|part| puzzle.parts().contains(part)) {
+        match bench::bench(*puzzle, *part, &input, &params, iterations) {
+          Ok(result) => benches.push(result),
+          Err(err) => {
+            eprintln!("{}", err);
+            bench_failed = true;
+          }
+        }
+      }
+    }
+
+    bench::print_table(&benches);
+
+    let commit = perf::git_commit();
+    let history = Path::new(perf::HISTORY_FILE);
+    let entries = perf::entries(&commit, &benches);
+    if entries.len() < benches.len() {
+      println!("Runs with --param overrides are not recorded");
+    }
+    match perf::append(history, &entries) {
+      Ok(()) if !entries.is_empty() => {
+        println!("Recorded in {} for commit {}", history.display(), commit)
+      }
+      Ok(()) => {}
+      Err(err) => {
+        eprintln!("{}", err);
+        bench_failed = true;
+      }
+    }
+
+    if bench_failed {
+      std::process::exit(1);
+    }
+    return;
+  }
+
+  let run_day = |puzzle: &&'static dyn Puzzle| {
+    let params = Params::new(puzzle.number(), puzzle.params(), &options.params);
+
+    let time = Instant::now();
+    match read_input(*puzzle) {
+      Some(input) => {
+        let load_elapsed = time.elapsed();
+        let mut runs = runner::run(*puzzle, &parts, &input, &params, &answers, options.timeout);
+        for run in &mut runs {
+          run.load_elapsed = load_elapsed;
+        }
+        runs
+      }
+      None => parts
+        .iter()
+        .map(|part| runner::Run::skipped(puzzle.number(), *part, runner::Status::NoInput))
+        .collect(),
+    }
+  };
+
+  // collecting an indexed parallel iterator keeps calendar order
+  let mut runs: Vec<runner::Run> = match options.parallel {
+    true => puzzles.par_iter().flat_map_iter(run_day).collect(),
+    false => puzzles.iter().flat_map(run_day).collect(),
+  };
+  runner::discard_shared_memory(&mut runs, options.parallel);
+
+  if options.format == Format::Json {
+    println!("{}", report::json(&runs));
+  } else if options.format == Format::Csv {
+    print!("{}", report::csv(&runs));
+  } else if verify {
+    runner::print_table(&runs);
+    if !runner::print_verify(&runs) {
+      std::process::exit(1);
+    }
+  } else if let [puzzle] = puzzles[..] {
+    println!("Day {}: {}", puzzle.number(), puzzle.title());
+
+    let memory = |usage: Option<Usage>| match usage {
+      Some(usage) => format!(
+        ", peak heap {} in {} allocations",
+        alloc::format_bytes(usage.peak),
+        usage.allocations
+      ),
+      None => "".to_string(),
+    };
+
+    if let Some(run) = runs.first().filter(|run| !run.load_elapsed.is_zero()) {
+      println!("\nLoad time: {}s", run.load_elapsed.as_secs_f32());
+    }
+    if let Some(run) = runs.iter().find(|run| !run.parse_elapsed.is_zero()) {
+      println!(
+        "Parse time: {}s{}",
+        run.parse_elapsed.as_secs_f32(),
+        memory(run.parse_memory)
+      );
+    }
+
+    let mut previous_error = None;
+    for run in &runs {
+      match (&run.answer, &run.error) {
+        (Some(answer), _) => {
+          println!("\nPart {}", run.part);
+          println!("Result: {}", answer);
+          println!("Time: {}s{}", run.elapsed.as_secs_f32(), memory(run.memory));
+        }
+        // a parse error fails both parts, only print it once
+        (None, Some(err @ Error::Parse { .. })) if previous_error == Some(err) => {}
+        (None, Some(err)) => {
+          eprintln!("\nPart {}: {}", run.part, err);
+          previous_error = Some(err);
+        }
+        (None, None) => println!("\nPart {}: not solved yet", run.part),
+      }
+    }
+  } else {
+    runner::print_table(&runs);
+  }
+
+  let failed = |status| {
+    matches!(status, runner::Status::Error | runner::Status::Timeout)
+      || verify && status == runner::Status::Fail
+  };
+  if runs.iter().any(|run| failed(run.status)) {
+    std::process::exit(1);
+  }
+}
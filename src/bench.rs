@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use crate::day::{Part, Puzzle};
+use crate::error::Result;
+use crate::input;
+use crate::params::Params;
+use crate::runner::format_duration;
+
+/// Untimed runs before the measured ones, to fill caches and let the CPU
+/// clock up.
+pub const WARMUP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+  pub min: Duration,
+  pub median: Duration,
+  pub mean: Duration,
+  pub p95: Duration,
+  pub stddev: Duration,
+}
+
+impl Stats {
+  /// Summarizes `samples`, `None` when there are none.
+  pub fn new(samples: &[Duration]) -> Option<Stats> {
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+
+    let count = sorted.len();
+    let min = *sorted.first()?;
+    let median = match count % 2 {
+      0 => (sorted[count / 2 - 1] + sorted[count / 2]) / 2,
+      _ => sorted[count / 2],
+    };
+    // nearest rank
+    let p95 = sorted[(count * 95).div_ceil(100) - 1];
+
+    let mean = sorted.iter().sum::<Duration>().as_secs_f64() / count as f64;
+    let variance = sorted
+      .iter()
+      .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+      .sum::<f64>()
+      / count as f64;
+
+    Some(Stats {
+      min,
+      median,
+      mean: Duration::from_secs_f64(mean),
+      p95,
+      stddev: Duration::from_secs_f64(variance.sqrt()),
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct Bench {
+  pub day: u8,
+  pub part: Part,
+  /// `input::checksum` of the input it ran on.
+  pub checksum: String,
+  /// Whether it ran with `--param` overrides, see `Params::overridden`.
+  pub overridden: bool,
+  pub iterations: usize,
+  pub parse: Stats,
+  pub solve: Stats,
+}
+
+/// Benchmarks one part on an input that is already in memory, so file I/O is
+/// never part of the numbers.
+pub fn bench(
+  puzzle: &dyn Puzzle,
+  part: Part,
+  input: &str,
+  params: &Params,
+  iterations: usize,
+) -> Result<Bench> {
+  puzzle.bench(part, input, params, WARMUP)?;
+  let (parse, solve) = puzzle.bench(part, input, params, iterations)?;
+
+  Ok(Bench {
+    day: puzzle.number(),
+    part,
+    checksum: input::checksum(input),
+    overridden: params.overridden(),
+    iterations,
+    parse: Stats::new(&parse).unwrap(),
+    solve: Stats::new(&solve).unwrap(),
+  })
+}
+
+pub fn print_table(benches: &[Bench]) {
+  println!(
+    "{:>3}  {:<4}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+    "Day", "Part", "Phase", "Min", "Median", "Mean", "P95", "Stddev"
+  );
+
+  for bench in benches {
+    for (phase, stats) in [("parse", &bench.parse), ("solve", &bench.solve)] {
+      println!(
+        "{:>3}  {:<4}  {:<5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+        bench.day,
+        bench.part,
+        phase,
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.mean),
+        format_duration(stats.p95),
+        format_duration(stats.stddev)
+      );
+    }
+  }
+
+  if let Some(bench) = benches.first() {
+    println!(
+      "\n{} iterations each, after {} warm-up runs",
+      bench.iterations, WARMUP
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn millis(samples: &[u64]) -> Vec<Duration> {
+    samples
+      .iter()
+      .map(|ms| Duration::from_millis(*ms))
+      .collect()
+  }
+
+  #[test]
+  fn test_stats() {
+    let stats = Stats::new(&millis(&[4, 2, 8, 6])).unwrap();
+
+    assert_eq!(stats.min, Duration::from_millis(2));
+    assert_eq!(stats.median, Duration::from_millis(5));
+    assert_eq!(stats.mean.as_micros(), 5000);
+    assert_eq!(stats.p95, Duration::from_millis(8));
+    assert_eq!(stats.stddev.as_micros(), 2236);
+  }
+
+  #[test]
+  fn test_stats_percentile() {
+    let samples = millis(&(1..=100).collect::<Vec<u64>>());
+
+    assert_eq!(Stats::new(&samples).unwrap().p95, Duration::from_millis(95));
+    assert_eq!(Stats::new(&[]), None);
+  }
+}
@@ -0,0 +1,325 @@
+use std::time::Duration;
+
+use crate::day::Part;
+use crate::input::Source;
+use crate::params;
+use crate::report::Format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+  /// Solve the selected days.
+  Run,
+  /// `verify`, re-run the days and flag answers that no longer match.
+  Verify,
+  /// `new <day>`, scaffold the module and files of a new day.
+  New,
+  /// `perf compare [baseline]`, compare the benchmark history of two commits.
+  Compare,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+  /// `all`, a day or a range, see `runner::select_days`.
+  pub days: String,
+  pub command: Command,
+  pub part: Option<Part>,
+  pub input: Source,
+  /// `--param name=value` overrides, in the order given.
+  pub params: Vec<(String, String)>,
+  /// `--bench N`, the number of timed iterations.
+  pub bench: Option<usize>,
+  pub format: Format,
+  /// `--parallel`, solve the days concurrently on the rayon pool.
+  pub parallel: bool,
+  /// `--threads N`, the size of the rayon pool, shared by the days and the
+  /// solvers that use rayon themselves.
+  pub threads: Option<usize>,
+  /// `--timeout <secs>`, how long a single part may take.
+  pub timeout: Option<Duration>,
+  /// `--watch`, re-run the day whenever its input, or its example file if it
+  /// has one, changes.
+  pub watch: bool,
+  /// The commit `perf compare` compares against.
+  pub baseline: Option<String>,
+  /// `--threshold <percent>`, the slowdown `perf compare` fails on.
+  pub threshold: Option<u32>,
+}
+
+pub const USAGE: &str =
+  "[verify] <day|all|first..last|first..=last> [part] [--input <file|->] [--param <name=value>]...\n  [--bench <N>] [--format <text|json|csv>] [--parallel] [--threads <N>]\n  [--timeout <secs>] [--watch]\n   or: new <day>\n   or: perf compare [baseline commit] [--threshold <percent>]";
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+  let mut args = args.into_iter();
+  let mut positional = vec![];
+  let mut input = Source::Default;
+  let mut params = vec![];
+  let mut bench = None;
+  let mut format = Format::Text;
+  let mut parallel = false;
+  let mut threads = None;
+  let mut timeout = None;
+  let mut watch = false;
+  let mut threshold = None;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--input" | "-i" => {
+        let value = args
+          .next()
+          .ok_or_else(|| format!("{} needs a file name or -", arg))?;
+        input = Source::from_arg(&value);
+      }
+      "--param" | "-p" => {
+        let value = args
+          .next()
+          .ok_or_else(|| format!("{} needs a name=value", arg))?;
+        params.push(params::parse_override(&value)?);
+      }
+      "--bench" => {
+        let iterations = args
+          .next()
+          .and_then(|value| value.parse::<usize>().ok())
+          .filter(|iterations| *iterations > 0)
+          .ok_or_else(|| format!("{} needs a number of iterations", arg))?;
+        bench = Some(iterations);
+      }
+      "--format" | "-f" => {
+        format = args
+          .next()
+          .ok_or_else(|| format!("{} needs text, json or csv", arg))?
+          .parse()?;
+      }
+      "--parallel" => parallel = true,
+      "--watch" => watch = true,
+      "--threads" => {
+        let count = args
+          .next()
+          .and_then(|value| value.parse::<usize>().ok())
+          .filter(|count| *count > 0)
+          .ok_or_else(|| format!("{} needs a number of threads", arg))?;
+        threads = Some(count);
+      }
+      "--timeout" => {
+        let limit = args
+          .next()
+          .and_then(|value| value.parse::<f64>().ok())
+          .filter(|secs| *secs > 0.0)
+          .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+          .ok_or_else(|| format!("{} needs a number of seconds", arg))?;
+        timeout = Some(limit);
+      }
+      "--threshold" => {
+        let percent = args
+          .next()
+          .and_then(|value| value.parse::<u32>().ok())
+          .ok_or_else(|| format!("{} needs a percentage", arg))?;
+        threshold = Some(percent);
+      }
+      flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+      _ => positional.push(arg),
+    }
+  }
+
+  let command = match positional.first().map(String::as_str) {
+    Some("verify") => Command::Verify,
+    Some("new") => Command::New,
+    Some("perf") => Command::Compare,
+    _ => Command::Run,
+  };
+  if command == Command::Compare && positional.get(1).map(String::as_str) != Some("compare") {
+    return Err("Expected perf compare".to_string());
+  }
+
+  let skip = match command {
+    Command::Run => 0,
+    Command::Compare => 2,
+    _ => 1,
+  };
+  let mut positional = positional.into_iter().skip(skip);
+  let mut baseline = None;
+  let days = match (positional.next(), command) {
+    (commit, Command::Compare) => {
+      baseline = commit;
+      "all".to_string()
+    }
+    (Some(days), _) => days,
+    (None, Command::Verify) => "all".to_string(),
+    (None, _) => return Err("Missing day".to_string()),
+  };
+  let part = match command {
+    Command::Compare => None,
+    _ => positional
+      .next()
+      .map(|part| part.parse::<Part>())
+      .transpose()?,
+  };
+
+  if let Some(extra) = positional.next() {
+    return Err(format!("Unexpected argument: {}", extra));
+  }
+
+  if command == Command::New && !matches!(days.parse::<u8>(), Ok(1..=25)) {
+    return Err(format!("new needs a day from 1 to 25, got {}", days));
+  }
+
+  if input != Source::Default && days.parse::<u8>().is_err() {
+    return Err("--input can only be used with a single day".to_string());
+  }
+
+  if bench.is_some() && format != Format::Text {
+    return Err("--bench only prints text".to_string());
+  }
+
+  if watch && (days.parse::<u8>().is_err() || input == Source::Stdin) {
+    return Err("--watch needs a single day and an input file".to_string());
+  }
+
+  if watch && (bench.is_some() || format != Format::Text) {
+    return Err("--watch only prints text".to_string());
+  }
+
+  if threshold.is_some() && command != Command::Compare {
+    return Err("--threshold only applies to perf compare".to_string());
+  }
+
+  if bench.is_some() && timeout.is_some() {
+    return Err("--bench runs every iteration to the end, drop --timeout".to_string());
+  }
+
+  if bench.is_some() && parallel {
+    return Err("--bench runs one day at a time, drop --parallel".to_string());
+  }
+
+  Ok(Options {
+    days,
+    command,
+    part,
+    input,
+    params,
+    bench,
+    format,
+    parallel,
+    threads,
+    timeout,
+    watch,
+    baseline,
+    threshold,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  fn args(args: &str) -> Vec<String> {
+    args.split_whitespace().map(|arg| arg.to_string()).collect()
+  }
+
+  #[test]
+  fn test_parse() {
+    assert_eq!(
+      parse(args("7 b --input day7.txt")),
+      Ok(Options {
+        days: "7".to_string(),
+        command: Command::Run,
+        part: Some(Part::B),
+        input: Source::File(PathBuf::from("day7.txt")),
+        params: vec![],
+        bench: None,
+        format: Format::Text,
+        parallel: false,
+        threads: None,
+        timeout: None,
+        watch: false,
+        baseline: None,
+        threshold: None,
+      })
+    );
+    assert_eq!(
+      parse(args("all")),
+      Ok(Options {
+        days: "all".to_string(),
+        command: Command::Run,
+        part: None,
+        input: Source::Default,
+        params: vec![],
+        bench: None,
+        format: Format::Text,
+        parallel: false,
+        threads: None,
+        timeout: None,
+        watch: false,
+        baseline: None,
+        threshold: None,
+      })
+    );
+    assert_eq!(
+      parse(args("11 b --param expansion=10 -p expansion=100"))
+        .unwrap()
+        .params,
+      vec![
+        ("expansion".to_string(), "10".to_string()),
+        ("expansion".to_string(), "100".to_string()),
+      ]
+    );
+    assert_eq!(parse(args("3 -i -")).unwrap().input, Source::Stdin);
+    assert_eq!(parse(args("all --bench 100")).unwrap().bench, Some(100));
+    assert_eq!(parse(args("all -f csv")).unwrap().format, Format::Csv);
+
+    let parallel = parse(args("all --parallel --threads 4")).unwrap();
+    assert!(parallel.parallel);
+    assert_eq!(parallel.threads, Some(4));
+    assert!(parse(args("7 b --watch")).unwrap().watch);
+    assert_eq!(
+      parse(args("8 --timeout 2.5")).unwrap().timeout,
+      Some(Duration::from_millis(2500))
+    );
+
+    let verify = parse(args("verify")).unwrap();
+    assert_eq!(verify.command, Command::Verify);
+    assert_eq!(verify.days, "all");
+    assert_eq!(parse(args("verify 3..6")).unwrap().days, "3..6");
+
+    let compare = parse(args("perf compare 1a2b --threshold 5")).unwrap();
+    assert_eq!(compare.command, Command::Compare);
+    assert_eq!(compare.baseline, Some("1a2b".to_string()));
+    assert_eq!(compare.threshold, Some(5));
+    assert_eq!(parse(args("perf compare")).unwrap().baseline, None);
+
+    let new = parse(args("new 12")).unwrap();
+    assert_eq!(new.command, Command::New);
+    assert_eq!(new.days, "12");
+  }
+
+  #[test]
+  fn test_parse_invalid() {
+    assert!(parse(args("")).is_err());
+    assert!(parse(args("3 c")).is_err());
+    assert!(parse(args("3 a b")).is_err());
+    assert!(parse(args("3 --input")).is_err());
+    assert!(parse(args("all --input day1.txt")).is_err());
+    assert!(parse(args("3 --verbose")).is_err());
+    assert!(parse(args("11 --param expansion")).is_err());
+    assert!(parse(args("3 --bench 0")).is_err());
+    assert!(parse(args("3 --bench")).is_err());
+    assert!(parse(args("3 --format xml")).is_err());
+    assert!(parse(args("3 --bench 10 --format json")).is_err());
+    assert!(parse(args("verify 3 a b")).is_err());
+    assert!(parse(args("all --threads 0")).is_err());
+    assert!(parse(args("all --parallel --bench 10")).is_err());
+    assert!(parse(args("8 --timeout 0")).is_err());
+    assert!(parse(args("all --watch")).is_err());
+    assert!(parse(args("7 --watch -i -")).is_err());
+    assert!(parse(args("7 --watch -f json")).is_err());
+    assert!(parse(args("8 --timeout soon")).is_err());
+    assert!(parse(args("8 --timeout 1e30")).is_err());
+    assert!(parse(args("perf")).is_err());
+    assert!(parse(args("perf compare a b")).is_err());
+    assert!(parse(args("all --threshold 5")).is_err());
+    assert!(parse(args("new")).is_err());
+    assert!(parse(args("new 26")).is_err());
+    assert!(parse(args("new all")).is_err());
+  }
+}
@@ -0,0 +1,192 @@
+//! The traits every day implements, and the one the runner uses.
+
+use std::fmt;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::alloc::{Measure, Usage};
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::params::{Param, Params};
+
+/// One of the two halves of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+  /// The first half.
+  A,
+  /// The second half, only revealed once the first is solved.
+  B,
+}
+
+impl FromStr for Part {
+  type Err = String;
+
+  fn from_str(s: &str) -> std::result::Result<Part, String> {
+    match s {
+      "a" | "A" => Ok(Part::A),
+      "b" | "B" => Ok(Part::B),
+      other => Err(format!("Unknown part: {}", other)),
+    }
+  }
+}
+
+impl fmt::Display for Part {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.pad(match self {
+      Part::A => "a",
+      Part::B => "b",
+    })
+  }
+}
+
+/// A single puzzle day. Every `dayN` module implements this on a unit struct
+/// and lists it in the `days!` registry in `lib.rs`.
+pub trait Day {
+  /// Whatever `parse` produces and both parts consume.
+  type Input;
+
+  /// The day of December the puzzle is from.
+  const NUMBER: u8;
+  /// The puzzle's title.
+  const TITLE: &'static str;
+  /// Parts that have a working solution, the runner skips the rest.
+  const PARTS: &'static [Part] = &[Part::A, Part::B];
+  /// Knobs that can be changed with `--param name=value`.
+  const PARAMS: &'static [Param] = &[];
+
+  /// Turns the puzzle input into `Input`.
+  fn parse(input: &str) -> Result<Self::Input>;
+  /// Solves part a on the parsed input.
+  fn part_a(input: &Self::Input, params: &Params) -> Result<Answer>;
+  /// Solves part b on the parsed input.
+  fn part_b(input: &Self::Input, params: &Params) -> Result<Answer>;
+}
+
+/// What `Puzzle::solve_parts` reports as it goes, with the time each phase
+/// took and its heap use, see `alloc::Counting`.
+#[derive(Debug)]
+pub enum Progress {
+  /// The input was parsed.
+  Parsed(Duration, Option<Usage>),
+  /// A part came up with an answer, or failed.
+  Solved(Part, Result<Answer>, Duration, Option<Usage>),
+}
+
+/// Object safe view of a `Day`, so days with different `Input` types can live
+/// in one registry.
+pub trait Puzzle: Sync {
+  /// `Day::NUMBER`
+  fn number(&self) -> u8;
+  /// `Day::TITLE`
+  fn title(&self) -> &'static str;
+  /// `Day::PARTS`
+  fn parts(&self) -> &'static [Part];
+  /// `Day::PARAMS`
+  fn params(&self) -> &'static [Param];
+  /// Parses `input` and solves `part` on it.
+  fn solve(&self, part: Part, input: &str, params: &Params) -> Result<Answer>;
+  /// Parses `input` once and solves each of `parts` on the result, timing the
+  /// parse and every part separately. Each phase is handed to `report` as soon
+  /// as it is done, a parse error is returned instead. A part that panics is
+  /// reported as `Error::Panic` and the next part still runs.
+  fn solve_parts(
+    &self,
+    parts: &[Part],
+    input: &str,
+    params: &Params,
+    report: &mut dyn FnMut(Progress),
+  ) -> Result<()>;
+  /// Times `iterations` parses of `input`, then `iterations` solves of `part`
+  /// on the last parse.
+  fn bench(
+    &self,
+    part: Part,
+    input: &str,
+    params: &Params,
+    iterations: usize,
+  ) -> Result<(Vec<Duration>, Vec<Duration>)>;
+}
+
+impl<D: Day + Sync> Puzzle for D {
+  fn number(&self) -> u8 {
+    D::NUMBER
+  }
+
+  fn title(&self) -> &'static str {
+    D::TITLE
+  }
+
+  fn parts(&self) -> &'static [Part] {
+    D::PARTS
+  }
+
+  fn params(&self) -> &'static [Param] {
+    D::PARAMS
+  }
+
+  fn solve(&self, part: Part, input: &str, params: &Params) -> Result<Answer> {
+    let input = D::parse(input)?;
+
+    match part {
+      Part::A => D::part_a(&input, params),
+      Part::B => D::part_b(&input, params),
+    }
+  }
+
+  fn solve_parts(
+    &self,
+    parts: &[Part],
+    input: &str,
+    params: &Params,
+    report: &mut dyn FnMut(Progress),
+  ) -> Result<()> {
+    let measure = Measure::start();
+    let time = Instant::now();
+    let input = D::parse(input)?;
+    report(Progress::Parsed(time.elapsed(), measure.finish()));
+
+    for part in parts {
+      let measure = Measure::start();
+      let time = Instant::now();
+      let answer = panic::catch_unwind(AssertUnwindSafe(|| match part {
+        Part::A => D::part_a(&input, params),
+        Part::B => D::part_b(&input, params),
+      }))
+      .unwrap_or_else(|payload| Err(Error::panic(D::NUMBER, payload.as_ref())));
+      let elapsed = time.elapsed();
+      report(Progress::Solved(*part, answer, elapsed, measure.finish()));
+    }
+
+    Ok(())
+  }
+
+  fn bench(
+    &self,
+    part: Part,
+    input: &str,
+    params: &Params,
+    iterations: usize,
+  ) -> Result<(Vec<Duration>, Vec<Duration>)> {
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut parsed = D::parse(input)?;
+    for _ in 0..iterations {
+      let time = Instant::now();
+      parsed = black_box(D::parse(black_box(input))?);
+      parse_times.push(time.elapsed());
+    }
+
+    let mut solve_times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+      let time = Instant::now();
+      black_box(match part {
+        Part::A => D::part_a(black_box(&parsed), params)?,
+        Part::B => D::part_b(black_box(&parsed), params)?,
+      });
+      solve_times.push(time.elapsed());
+    }
+
+    Ok((parse_times, solve_times))
+  }
+}
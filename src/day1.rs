@@ -1,10 +1,12 @@
-use std::{
-  fs,
-  io::{BufRead, BufReader},
-};
+//! Day 1: Trebuchet?!, a line's calibration value is its first and last digit.
 
 use regex::Regex;
 
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::Result;
+use crate::params::Params;
+
 #[derive(Debug)]
 struct Pair {
   first: u32,
@@ -21,17 +23,19 @@ impl PairTrait for Pair {
   }
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
-    .lines()
+/// The calibration document, one string per line.
+pub fn parse_input(input: &str) -> Result<Vec<String>> {
+  Ok(input.lines().map(|line| line.to_string()).collect())
+}
+
+/// Sums the calibration values, counting only the digits.
+pub fn solve_a(lines: &[String]) -> Result<Answer> {
+  let sum = lines
+    .iter()
     .map(|line| {
       line
-        .unwrap()
         .chars()
-        .map(|c| match c.to_digit(10) {
-          Some(n) => n,
-          None => 0,
-        })
+        .map(|c| c.to_digit(10).unwrap_or_default())
         .collect::<Vec<u32>>()
     })
     .map(|v| {
@@ -50,18 +54,19 @@ pub fn solve_a(input_file_path: &str) -> u64 {
       })
     })
     .map(|f| f.result())
-    .sum::<u32>()
-    .try_into()
-    .unwrap()
+    .sum::<u32>();
+
+  Ok(sum.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
-        .lines()
+/// Sums the calibration values, counting spelled out digits like `one` too.
+pub fn solve_b(lines: &[String]) -> Result<Answer> {
+  let sum = lines
+        .iter()
         .map(|line| {
             Regex::new(r"(oneight|twone|threeight|fiveight|sevenine|eightwo|eighthree|nineight|one|two|three|four|five|six|seven|eight|nine|\d)")
                 .unwrap()
-                .find_iter(line.unwrap().as_str())
+                .find_iter(line)
                 .flat_map(|m| match m.as_str() {
                     "one" => vec![1],
                     "two" => vec![2],
@@ -107,22 +112,62 @@ pub fn solve_b(input_file_path: &str) -> u64 {
             })
         )
         .map(|f| f.result())
-        .sum::<u32>()
-        .try_into()
-        .unwrap()
+        .sum::<u32>();
+
+  Ok(sum.into())
+}
+
+/// The puzzle, see `Day`.
+pub struct Day1;
+
+impl Day for Day1 {
+  type Input = Vec<String>;
+
+  const NUMBER: u8 = 1;
+  const TITLE: &'static str = "Trebuchet?!";
+
+  fn parse(input: &str) -> Result<Vec<String>> {
+    parse_input(input)
+  }
+
+  fn part_a(lines: &Vec<String>, _: &Params) -> Result<Answer> {
+    solve_a(lines)
+  }
+
+  fn part_b(lines: &Vec<String>, _: &Params) -> Result<Answer> {
+    solve_b(lines)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE_A: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+  const EXAMPLE_B: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day1a.txt"), 142);
+    assert_eq!(solve_a(&parse_input(EXAMPLE_A).unwrap()), Ok(142.into()));
+    // spelled out digits do not count in part a
+    assert_eq!(solve_a(&parse_input(EXAMPLE_B).unwrap()), Ok(209.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day1b.txt"), 281);
+    assert_eq!(solve_b(&parse_input(EXAMPLE_B).unwrap()), Ok(281.into()));
+    assert_eq!(solve_b(&parse_input(EXAMPLE_A).unwrap()), Ok(142.into()));
   }
 }
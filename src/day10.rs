@@ -1,6 +1,10 @@
-#![allow(dead_code, unused_imports, unused_variables)]
-use colored::Colorize;
-use std::fs;
+//! Day 10: Pipe Maze, the loop of pipes through the start tile `S`.
+
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::grid::{Coord, Grid};
+use crate::params::Params;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
@@ -10,37 +14,27 @@ enum Direction {
   Right,
 }
 
-type Coord = (usize, usize);
-
 #[derive(Debug, Clone)]
 struct Pipe {
   symbol: char,
-  coord: Coord,
   connections: Vec<Direction>,
 }
 
+/// The pipe field with its main loop already traced from the start tile.
 #[derive(Debug)]
-struct Pipes {
+pub struct Pipes {
   start: Coord,
-  pipes: Vec<Vec<Pipe>>,
+  pipes: Grid<Pipe>,
   main_loop: Vec<Coord>,
 }
 
 impl Direction {
-  fn shift_coord(&self, coord: Coord) -> Coord {
-    if coord.0 == 0 && self == &Direction::Up {
-      return coord;
-    }
-
-    if coord.1 == 0 && self == &Direction::Left {
-      return coord;
-    }
-
+  fn offset(&self) -> (isize, isize) {
     match self {
-      Direction::Up => (coord.0 - 1, coord.1),
-      Direction::Down => (coord.0 + 1, coord.1),
-      Direction::Left => (coord.0, coord.1 - 1),
-      Direction::Right => (coord.0, coord.1 + 1),
+      Direction::Up => (-1, 0),
+      Direction::Down => (1, 0),
+      Direction::Left => (0, -1),
+      Direction::Right => (0, 1),
     }
   }
 
@@ -55,10 +49,9 @@ impl Direction {
 }
 
 impl Pipe {
-  fn new(symbol: char, y: usize, x: usize) -> Pipe {
-    Pipe {
+  fn new(symbol: char) -> Option<Pipe> {
+    Some(Pipe {
       symbol,
-      coord: (y, x),
       connections: match symbol {
         '7' => vec![Direction::Left, Direction::Down],
         'J' => vec![Direction::Up, Direction::Left],
@@ -73,214 +66,226 @@ impl Pipe {
           Direction::Left,
           Direction::Right,
         ],
-        x => panic!("Unknown symbol: {}", x),
+        _ => return None,
       },
-    }
+    })
   }
 }
 
 impl Pipes {
-  fn new(input: &str) -> Pipes {
-    let mut pipes = vec![];
-    let mut start = (0, 0);
-
-    for (y, line) in input.lines().enumerate() {
-      let mut row = vec![];
-
-      for (x, symbol) in line.chars().enumerate() {
-        row.push(Pipe::new(symbol, y, x));
-
-        if symbol == 'S' {
-          start = (y, x);
-        }
-      }
-
-      pipes.push(row);
-    }
+  fn new(input: &str) -> Result<Pipes> {
+    let pipes = Grid::parse(Day10::NUMBER, input, Pipe::new)?;
+    let start = pipes
+      .position(|pipe| pipe.symbol == 'S')
+      .ok_or_else(|| Error::unsolvable(Day10::NUMBER, "no start tile S"))?;
 
     let mut pipes = Pipes {
       start,
       pipes,
       main_loop: vec![],
     };
-    pipes.solve();
+    pipes.solve()?;
 
-    pipes
+    Ok(pipes)
   }
 
-  fn solve(&mut self) -> &Self {
-    let mut current_pipe: &Pipe = self.get(self.start).unwrap();
-    let mut previous_pipe: &Pipe = current_pipe;
+  /// Follows the pipes from `S` until they get back to it, an error when
+  /// they dead-end first.
+  fn solve(&mut self) -> Result<&Self> {
+    let mut current = self.start;
+    let mut previous = current;
     let mut main_loop = vec![];
 
     loop {
-      let next_pipe = self.next(current_pipe, previous_pipe);
+      main_loop.push(current);
 
-      if next_pipe.is_none() || next_pipe.unwrap().symbol == 'S' {
-        main_loop.push(current_pipe.coord);
-        break;
+      match self.next(current, previous) {
+        Some(next) if next == self.start => break,
+        Some(next) => {
+          previous = current;
+          current = next;
+        }
+        None => {
+          return Err(Error::unsolvable(
+            Day10::NUMBER,
+            "the loop from S does not close",
+          ))
+        }
       }
-
-      main_loop.push(current_pipe.coord);
-      previous_pipe = current_pipe;
-      current_pipe = next_pipe.unwrap();
     }
 
     self.main_loop = main_loop;
-    self
+    Ok(self)
   }
 
-  fn get(&self, coord: Coord) -> Option<&Pipe> {
-    if coord.0 >= self.pipes.len() || coord.1 >= self.pipes[coord.0].len() {
-      return None;
-    }
-
-    Some(&self.pipes[coord.0][coord.1])
+  /// The tile the loop continues on from `current` when coming from
+  /// `previous`.
+  fn next(&self, current: Coord, previous: Coord) -> Option<Coord> {
+    self.pipes[current]
+      .connections
+      .iter()
+      .find_map(|direction| {
+        self
+          .pipes
+          .step(current, direction.offset())
+          .filter(|next| *next != previous)
+          .filter(|next| self.pipes[*next].connections.contains(&direction.reverse()))
+      })
   }
+}
 
-  fn next(&self, current_pipe: &Pipe, prev_pipe: &Pipe) -> Option<&Pipe> {
-    let mut next_pipe = None;
+/// The map of pipes, with the loop from `S` already followed.
+pub fn parse_input(input: &str) -> Result<Pipes> {
+  Pipes::new(input)
+}
 
-    for direction in &current_pipe.connections {
-      let next_coord = direction.shift_coord(current_pipe.coord);
+/// Steps along the loop to the tile farthest from `S`.
+pub fn solve_a(pipes: &Pipes) -> Result<Answer> {
+  Ok(pipes.main_loop.len().div_ceil(2).into())
+}
 
-      if next_coord == prev_pipe.coord {
-        continue;
-      }
+/// Counts the tiles enclosed by the main loop: the shoelace formula gives the
+/// loop's area, Pick's theorem turns that into the number of inner points.
+pub fn solve_b(pipes: &Pipes) -> Result<Answer> {
+  let main_loop = &pipes.main_loop;
 
-      let next_pipe_candidate = self.get(next_coord);
+  let twice_area = main_loop
+    .iter()
+    .zip(main_loop.iter().cycle().skip(1))
+    .map(|(a, b)| (a.1 * b.0) as i64 - (b.1 * a.0) as i64)
+    .sum::<i64>()
+    .unsigned_abs() as usize;
 
-      if next_pipe_candidate.is_none() {
-        continue;
-      }
+  Ok(((twice_area + 2).saturating_sub(main_loop.len()) / 2).into())
+}
 
-      let next_pipe_candidate = next_pipe_candidate.unwrap();
+/// The puzzle, see `Day`.
+pub struct Day10;
 
-      if next_pipe_candidate
-        .connections
-        .contains(&direction.reverse())
-      {
-        next_pipe = Some(next_pipe_candidate);
-        break;
-      }
-    }
-    next_pipe
-  }
+impl Day for Day10 {
+  type Input = Pipes;
 
-  // fn expand(&mut self) -> &Self {
-  //   let mut pipes = vec![];
-  //   let mut expanded_main_loop = vec![];
-
-  //   for (y, row) in self.pipes.iter().enumerate() {
-  //     let mut new_row = vec![];
-
-  //     for (x, pipe) in row.iter().enumerate() {
-  //       if pipe.symbol == '.' || !self.main_loop.contains(&pipe.coord) {
-  //         new_row.push(Pipe::new('O', 2 * y, 2 * x));
-  //         new_row.push(Pipe::new('#', 2 * y, 2 * x + 1));
-  //       } else {
-  //         new_row.push(Pipe::new(pipe.symbol, 2 * y, 2 * x));
-  //         new_row.push(Pipe::new('#', 2 * y, 2 * x + 1));
-  //       }
-  //     }
-  //     pipes.push(new_row);
-
-  //     let empty_row = (0..row.len() * 2)
-  //       .map(|x| Pipe::new('#', 2 * y + 1, 2 * x))
-  //       .collect::<Vec<Pipe>>();
-  //     pipes.push(empty_row);
-  //   }
-
-  //   self.pipes = pipes;
-  //   self.main_loop = self
-  //     .main_loop
-  //     .iter()
-  //     .map(|coord| (2 * coord.0, 2 * coord.1))
-  //     .collect();
-
-  //   self.main_loop.append(&mut expanded_main_loop);
-
-  //   // TODO - WTF is going on
-  //   for y in 1..self.pipes.len() / 2 {
-  //     for x in 1..self.pipes[y].len() / 2 {
-  //       let left_pipe = self.get((2 * y, 2 * x - 1));
-  //       let right_pipe = self.get((2 * y, 2 * x + 1));
-  //       let up_pipe = self.get((2 * y - 1, 2 * x));
-  //       let down_pipe = self.get((2 * y + 1, 2 * x));
-
-  //       if left_pipe.is_some() && right_pipe.is_some() {
-  //         let left_pipe = left_pipe.unwrap();
-  //         let right_pipe = right_pipe.unwrap();
-
-  //         if ['-', 'L', 'F', 'S'].contains(&left_pipe.symbol)
-  //           && ['-', 'J', 'F', 'S'].contains(&right_pipe.symbol)
-  //         {
-  //           self.pipes[2 * y][2 * x].symbol = '-';
-  //           self.main_loop.push((2 * y, 2 * x));
-  //         }
-  //       }
-
-  //       if up_pipe.is_some() && down_pipe.is_some() {
-  //         let up_pipe = up_pipe.unwrap();
-  //         let down_pipe = down_pipe.unwrap();
-
-  //         if ['|', '7', 'J', 'S'].contains(&up_pipe.symbol)
-  //           && ['|', '7', 'L', 'S'].contains(&down_pipe.symbol)
-  //         {
-  //           self.pipes[2 * y][2 * x].symbol = '|';
-  //           self.main_loop.push((2 * y, 2 * x));
-  //         }
-  //       }
-  //     }
-  //   }
-
-  //   self
-  // }
-
-  fn debug(&self) -> &Self {
-    for row in &self.pipes {
-      for pipe in row {
-        if self.main_loop.contains(&pipe.coord) {
-          print!("{}", pipe.symbol.to_string().red());
-        } else {
-          print!("{}", pipe.symbol);
-        }
-      }
-      println!();
-    }
+  const NUMBER: u8 = 10;
+  const TITLE: &'static str = "Pipe Maze";
 
-    self
+  fn parse(input: &str) -> Result<Pipes> {
+    parse_input(input)
   }
-}
-
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let input = fs::read_to_string(input_file_path).unwrap();
-  let pipes = Pipes::new(&input);
-
-  pipes.debug();
-
-  ((pipes.main_loop.len() + 1) / 2) as u64
-}
 
-// SKIP - too hard :(
-pub fn solve_b(input_file_path: &str) -> u64 {
-  // let input = fs::read_to_string(input_file_path).unwrap();
-  // let pipes = Pipes::new(&input).expand().debug();
+  fn part_a(pipes: &Pipes, _: &Params) -> Result<Answer> {
+    solve_a(pipes)
+  }
 
-  10
+  fn part_b(pipes: &Pipes, _: &Params) -> Result<Answer> {
+    solve_b(pipes)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE_A1: &str = "\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF";
+
+  const EXAMPLE_A2: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+  const EXAMPLE_B1: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+  const EXAMPLE_B2: &str = "\
+..........
+.S------7.
+.|F----7|.
+.||OOOO||.
+.||OOOO||.
+.|L-7F-J|.
+.|II||II|.
+.L--JL--J.
+..........";
+
+  const EXAMPLE_B3: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+  const EXAMPLE_B4: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+
+  const EXAMPLES: [&str; 6] = [
+    EXAMPLE_A1, EXAMPLE_A2, EXAMPLE_B1, EXAMPLE_B2, EXAMPLE_B3, EXAMPLE_B4,
+  ];
+
+  fn solve_all(solve: fn(&Pipes) -> Result<Answer>) -> Vec<Answer> {
+    EXAMPLES
+      .iter()
+      .map(|example| solve(&parse_input(example).unwrap()).unwrap())
+      .collect()
+  }
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day10a.txt"), 8);
+    let answers: Vec<Answer> = vec![4, 8, 23, 22, 70, 80]
+      .into_iter()
+      .map(Answer::from)
+      .collect();
+    assert_eq!(solve_all(solve_a), answers);
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day10b.txt"), 10);
+    let answers: Vec<Answer> = vec![1, 1, 4, 4, 8, 10]
+      .into_iter()
+      .map(Answer::from)
+      .collect();
+    assert_eq!(solve_all(solve_b), answers);
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("..F7.\n.FJ|.\nSJ.X7") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (3, 4)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_open_loop() {
+    for input in ["S-7\n...", "S"] {
+      assert!(matches!(parse_input(input), Err(Error::Unsolvable { .. })));
+    }
   }
 }
@@ -1,43 +1,40 @@
-#![allow(dead_code, unused_imports, unused_variables)]
-use std::fs;
+//! Day 11: Cosmic Expansion, distances between galaxies in a growing universe.
 
-type Coord = (u128, u128);
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::params::{Param, Params};
 
-#[derive(Debug)]
-struct Cell {
-  coord: Coord,
-  symbol: char,
-}
+type Coord = (u128, u128);
 
-#[derive(Debug)]
-struct Galaxy {
+/// The galaxies in an image, with the empty rows and columns that expand.
+#[derive(Debug, Clone)]
+pub struct Galaxy {
   planets: Vec<Coord>,
   empty_rows: Vec<u128>,
   empty_cols: Vec<u128>,
 }
 
 impl Galaxy {
-  fn new(input: &str) -> Self {
-    let mut planets: Vec<Coord> = Vec::new();
-    let empty_rows: Vec<u128> = input
-      .lines()
+  /// Reads the galaxies from `image`, where `true` marks a galaxy.
+  pub fn new(image: &Grid<bool>) -> Self {
+    let planets = image
+      .iter()
+      .filter(|(_, galaxy)| **galaxy)
+      .map(|((y, x), _)| (y as u128, x as u128))
+      .collect();
+    let empty_rows = image
+      .rows()
       .enumerate()
-      .filter(|(_, line)| line.chars().all(|c| c == '.'))
-      .map(|(i, _)| i as u128)
+      .filter(|(_, row)| !row.contains(&true))
+      .map(|(y, _)| y as u128)
+      .collect();
+    let empty_cols = image
+      .columns()
+      .enumerate()
+      .filter_map(|(x, mut col)| (!col.any(|galaxy| *galaxy)).then_some(x as u128))
       .collect();
-    let mut empty_cols: Vec<u128> = input.lines().enumerate().map(|(x, _)| x as u128).collect();
-
-    for (y, line) in input.lines().enumerate() {
-      for (x, symbol) in line.chars().enumerate() {
-        match symbol {
-          '#' => {
-            planets.push((y as u128, x as u128));
-            empty_cols.retain(|&i| i != x as u128);
-          }
-          _ => {}
-        }
-      }
-    }
 
     Galaxy {
       planets,
@@ -46,32 +43,33 @@ impl Galaxy {
     }
   }
 
-  fn expand(&mut self, expansion_multiplier: u128) -> &Self {
+  /// Moves the galaxies apart as if every empty row and column were
+  /// `expansion_multiplier` times as wide, `None` when a coordinate no
+  /// longer fits in a `u128`.
+  pub fn expand(&mut self, expansion_multiplier: u128) -> Option<&Self> {
+    let extra = expansion_multiplier.checked_sub(1)?;
+    let shift = |position: u128, empty: &[u128]| {
+      let before = empty.iter().filter(|&&line| position > line).count() as u128;
+      extra.checked_mul(before)?.checked_add(position)
+    };
+
     self.planets = self
       .planets
       .iter()
       .map(|planet| {
-        let mut expand_distace: Coord = (0, 0);
-
-        self.empty_cols.iter().for_each(|&col| {
-          if planet.1 > col {
-            expand_distace.1 += expansion_multiplier - 1;
-          }
-        });
-        self.empty_rows.iter().for_each(|&row| {
-          if planet.0 > row {
-            expand_distace.0 += expansion_multiplier - 1;
-          }
-        });
-
-        (planet.0 + expand_distace.0, planet.1 + expand_distace.1)
+        Some((
+          shift(planet.0, &self.empty_rows)?,
+          shift(planet.1, &self.empty_cols)?,
+        ))
       })
-      .collect();
+      .collect::<Option<Vec<Coord>>>()?;
 
-    self
+    Some(self)
   }
 
-  fn manhattan_distances(&self) -> Vec<u64> {
+  /// For every galaxy, the sum of its distances to all the others, `None`
+  /// when a sum does not fit in a `u128`.
+  pub fn manhattan_distances(&self) -> Option<Vec<u128>> {
     self
       .planets
       .iter()
@@ -81,47 +79,141 @@ impl Galaxy {
           .planets
           .iter()
           .enumerate()
-          .filter_map(move |(j, other_planet)| {
-            if i != j {
-              Some(manhattan_distance(*planet, *other_planet))
-            } else {
-              None
-            }
+          .filter(|(j, _)| i != *j)
+          .try_fold(0u128, |sum, (_, other_planet)| {
+            sum.checked_add(manhattan_distance(*planet, *other_planet)?)
           })
-          .sum::<u64>()
       })
-      .collect::<Vec<u64>>()
+      .collect()
   }
 }
 
-fn manhattan_distance(a: Coord, b: Coord) -> u64 {
-  ((a.0 as i64 - b.0 as i64).abs() + (a.1 as i64 - b.1 as i64).abs()) as u64
+fn manhattan_distance(a: Coord, b: Coord) -> Option<u128> {
+  a.0.abs_diff(b.0).checked_add(a.1.abs_diff(b.1))
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let mut galaxy = Galaxy::new(&fs::read_to_string(input_file_path).unwrap());
-  galaxy.expand(2);
-  galaxy.manhattan_distances().iter().sum::<u64>() / 2
+/// The image, `#` for a galaxy and `.` for empty space.
+pub fn parse_input(input: &str) -> Result<Galaxy> {
+  let image = Grid::parse(Day11::NUMBER, input, |c| match c {
+    '#' => Some(true),
+    '.' => Some(false),
+    _ => None,
+  })?;
+
+  Ok(Galaxy::new(&image))
+}
+
+/// The sum of the distances between every pair of galaxies after expanding
+/// them, `None` when it overflows.
+fn total_distance(galaxy: &Galaxy, expansion_multiplier: u128) -> Option<u128> {
+  let mut galaxy = galaxy.clone();
+  let distances = galaxy.expand(expansion_multiplier)?.manhattan_distances()?;
+
+  distances
+    .iter()
+    .try_fold(0u128, |sum, distance| sum.checked_add(*distance))
+    .map(|sum| sum / 2)
 }
 
-pub fn solve_b(input_file_path: &str, expansion_multiplier: u128) -> u64 {
-  let mut galaxy = Galaxy::new(&fs::read_to_string(input_file_path).unwrap());
-  galaxy.expand(expansion_multiplier);
-  galaxy.manhattan_distances().iter().sum::<u64>() / 2
+/// Sums the distances between every pair of galaxies, with empty rows and
+/// columns twice as wide.
+pub fn solve_a(galaxy: &Galaxy) -> Result<Answer> {
+  total_distance(galaxy, 2)
+    .map(Answer::from)
+    .ok_or_else(|| Error::unsolvable(Day11::NUMBER, "the distances overflow a u128"))
+}
+
+/// Sums the distances between every pair of galaxies, with empty rows and
+/// columns `expansion_multiplier` times as wide.
+pub fn solve_b(galaxy: &Galaxy, expansion_multiplier: u128) -> Result<Answer> {
+  let error = |message: &str| Error::Param {
+    day: Day11::NUMBER,
+    name: "expansion".to_string(),
+    message: message.to_string(),
+  };
+
+  if expansion_multiplier < 1 {
+    return Err(error("must be at least 1"));
+  }
+
+  total_distance(galaxy, expansion_multiplier)
+    .map(Answer::from)
+    .ok_or_else(|| error("too large, the distances overflow a u128"))
+}
+
+/// The puzzle, see `Day`.
+pub struct Day11;
+
+impl Day for Day11 {
+  type Input = Galaxy;
+
+  const NUMBER: u8 = 11;
+  const TITLE: &'static str = "Cosmic Expansion";
+  const PARAMS: &'static [Param] = &[Param {
+    name: "expansion",
+    default: "1000000",
+    description: "how many times wider empty rows and columns get (part b)",
+  }];
+
+  fn parse(input: &str) -> Result<Galaxy> {
+    parse_input(input)
+  }
+
+  fn part_a(galaxy: &Galaxy, _: &Params) -> Result<Answer> {
+    solve_a(galaxy)
+  }
+
+  fn part_b(galaxy: &Galaxy, params: &Params) -> Result<Answer> {
+    solve_b(galaxy, params.get("expansion")?)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day11.txt"), 374);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(374.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day11.txt", 10), 1030);
-    assert_eq!(solve_b(".\\src\\test_input\\day11.txt", 100), 8410);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap(), 10), Ok(1030.into()));
+    assert_eq!(
+      solve_b(&parse_input(EXAMPLE).unwrap(), 100),
+      Ok(8410.into())
+    );
+  }
+
+  #[test]
+  fn test_wide_image() {
+    // the columns past the number of rows expand too
+    assert_eq!(solve_a(&parse_input("#...#\n.....").unwrap()), Ok(7.into()));
+  }
+
+  #[test]
+  fn test_expansion_out_of_range() {
+    let galaxy = parse_input(EXAMPLE).unwrap();
+
+    for expansion in [0, u128::MAX / 2] {
+      assert!(matches!(
+        solve_b(&galaxy, expansion),
+        Err(Error::Param { .. })
+      ));
+    }
+    assert_eq!(solve_b(&galaxy, 1), Ok(292.into()));
   }
 }
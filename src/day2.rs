@@ -1,48 +1,76 @@
-use std::fs;
-use std::io::{BufRead, BufReader};
+//! Day 2: Cube Conundrum, games of drawing coloured cubes from a bag.
 
-fn parse_input(input: &str) -> (u32, Vec<(u32, String)>) {
-  let binding = input;
-  let (score, game) = binding.split_once(": ").unwrap();
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::{Param, Params};
 
-  let score = score.split_once(" ").unwrap().1;
+/// A game id with every `(amount, color)` revealed in it.
+pub type Game = (u32, Vec<(u32, String)>);
+
+fn parse_game(index: usize, line: &str) -> Result<Game> {
+  let error = |token: &str, message: &str| Error::parse(Day2::NUMBER, index, line, token, message);
+
+  let (score, game) = line
+    .split_once(": ")
+    .ok_or_else(|| error(line, "expected `Game <id>: <cubes>`"))?;
+
+  let score = score
+    .split_once(' ')
+    .ok_or_else(|| error(score, "expected `Game <id>`"))?
+    .1;
 
   let game = game
-    .split(|c| c == ',' || c == ';')
+    .split([',', ';'])
     .map(|s| s.trim())
     .map(|cube| {
-      let (amount, color) = cube.split_once(' ').unwrap();
-      let amount = amount.parse::<u32>().unwrap();
+      let (amount, color) = cube
+        .split_once(' ')
+        .ok_or_else(|| error(cube, "expected `<amount> <color>`"))?;
+      let amount = parse_number::<u32>(Day2::NUMBER, index, line, amount)?;
+      if !["red", "green", "blue"].contains(&color) {
+        return Err(error(color, "unknown color"));
+      }
       let color = color.to_string();
-      (amount, color)
+      Ok((amount, color))
     })
-    .collect::<Vec<(u32, String)>>();
+    .collect::<Result<Vec<(u32, String)>>>()?;
 
-  (score.parse::<u32>().unwrap(), game)
+  Ok((parse_number(Day2::NUMBER, index, line, score)?, game))
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
+/// One game per line, `Game <id>: <amount> <color>, ...; ...`.
+pub fn parse_input(input: &str) -> Result<Vec<Game>> {
+  input
     .lines()
-    .map(|line| parse_input(&line.unwrap()))
+    .enumerate()
+    .map(|(index, line)| parse_game(index, line))
+    .collect()
+}
+
+/// Sums the ids of the games possible with `bag`, the red, green and blue
+/// cubes in the bag.
+pub fn solve_a(games: &[Game], bag: (u32, u32, u32)) -> Result<Answer> {
+  let sum = games
+    .iter()
     .filter(|(_, game)| {
       !game.iter().any(|(amount, color)| match color.as_str() {
-        "red" => amount > &12,
-        "green" => amount > &13,
-        "blue" => amount > &14,
+        "red" => amount > &bag.0,
+        "green" => amount > &bag.1,
+        "blue" => amount > &bag.2,
         _ => false,
       })
     })
     .map(|(score, _)| score)
-    .sum::<u32>()
-    .try_into()
-    .unwrap()
+    .sum::<u32>();
+
+  Ok(sum.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
-    .lines()
-    .map(|line| parse_input(&line.unwrap()))
+/// Sums the powers of the fewest cubes of each colour every game needs.
+pub fn solve_b(games: &[Game]) -> Result<Answer> {
+  let sum = games
+    .iter()
     .map(|line| {
       let game = line
         .1
@@ -74,22 +102,90 @@ pub fn solve_b(input_file_path: &str) -> u64 {
 
       game.0 * game.1 * game.2
     })
-    .sum::<u32>()
-    .try_into()
-    .unwrap()
+    .sum::<u32>();
+
+  Ok(sum.into())
+}
+
+/// The puzzle, see `Day`.
+pub struct Day2;
+
+impl Day for Day2 {
+  type Input = Vec<Game>;
+
+  const NUMBER: u8 = 2;
+  const TITLE: &'static str = "Cube Conundrum";
+  const PARAMS: &'static [Param] = &[
+    Param {
+      name: "red",
+      default: "12",
+      description: "red cubes in the bag (part a)",
+    },
+    Param {
+      name: "green",
+      default: "13",
+      description: "green cubes in the bag (part a)",
+    },
+    Param {
+      name: "blue",
+      default: "14",
+      description: "blue cubes in the bag (part a)",
+    },
+  ];
+
+  fn parse(input: &str) -> Result<Vec<Game>> {
+    parse_input(input)
+  }
+
+  fn part_a(games: &Vec<Game>, params: &Params) -> Result<Answer> {
+    let bag = (
+      params.get("red")?,
+      params.get("green")?,
+      params.get("blue")?,
+    );
+    solve_a(games, bag)
+  }
+
+  fn part_b(games: &Vec<Game>, _: &Params) -> Result<Answer> {
+    solve_b(games)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day2.txt"), 8);
+    assert_eq!(
+      solve_a(&parse_input(EXAMPLE).unwrap(), (12, 13, 14)),
+      Ok(8.into())
+    );
+    assert_eq!(
+      solve_a(&parse_input(EXAMPLE).unwrap(), (20, 13, 14)),
+      Ok(11.into())
+    );
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day2.txt"), 2286);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(2286.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("Game 1: 3 blue\nGame 2: 4 purple, 1 red") {
+      Err(Error::Parse {
+        day, line, column, ..
+      }) => assert_eq!((day, line, column), (2, 2, 11)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
   }
 }
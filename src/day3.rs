@@ -1,201 +1,205 @@
-use std::fs;
+//! Day 3: Gear Ratios, numbers next to symbols on an engine schematic.
 
-#[derive(Debug)]
+use std::collections::HashMap;
+
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::Result;
+use crate::grid::{Coord, Grid};
+use crate::params::{Param, Params};
+
+/// A number on the schematic, `end` is the column after its last digit.
+#[derive(Debug, Clone, Copy)]
 struct Number {
   value: u32,
-  position_start: (u32, u32),
-  position_end: (u32, u32),
+  row: usize,
+  start: usize,
+  end: usize,
 }
 
-impl Clone for Number {
-  fn clone(&self) -> Number {
-    Number {
-      value: self.value,
-      position_start: self.position_start,
-      position_end: self.position_end,
-    }
+impl Number {
+  /// The cells around the number, its own digits included.
+  fn adjacent(&self, board: &Grid<char>) -> Vec<Coord> {
+    let mut coords = (self.start..self.end)
+      .flat_map(|x| board.neighbours8((self.row, x)))
+      .collect::<Vec<Coord>>();
+    coords.sort();
+    coords.dedup();
+
+    coords
   }
 }
 
-impl Copy for Number {}
-
-impl PartialEq for Number {
-  fn eq(&self, other: &Self) -> bool {
-    self.value == other.value
-      && self.position_start == other.position_start
-      && self.position_end == other.position_end
-  }
-}
+fn numbers(board: &Grid<char>) -> Vec<Number> {
+  let mut numbers = vec![];
+
+  for (y, row) in board.rows().enumerate() {
+    let mut x = 0;
+    while x < row.len() {
+      let Some(digit) = row[x].to_digit(10) else {
+        x += 1;
+        continue;
+      };
+
+      let mut number = Number {
+        value: digit,
+        row: y,
+        start: x,
+        end: x + 1,
+      };
+      while let Some(digit) = row.get(number.end).and_then(|c| c.to_digit(10)) {
+        number.value = number.value * 10 + digit;
+        number.end += 1;
+      }
 
-impl Number {
-  fn new(char: char, position_start: (u32, u32), position_end: (u32, u32)) -> Number {
-    Number {
-      value: char.to_digit(10).unwrap(),
-      position_start,
-      position_end,
+      x = number.end;
+      numbers.push(number);
     }
   }
 
-  fn expand_number(&mut self, char: char, position: (u32, u32)) {
-    self.value *= 10;
-    self.value += char.to_digit(10).unwrap();
-    self.position_end = position;
-  }
-
-  fn generate_adjacent_coords(&self) -> Vec<(u32, u32)> {
-    let mut adjacent_coords: Vec<(u32, u32)> = Vec::new();
-
-    for x in self.position_start.0 as i32 - 1..=self.position_end.0 as i32 + 1 {
-      for y in self.position_start.1 as i32 - 1..=self.position_end.1 as i32 + 1 {
-        if x != 0 && y != 0 && x.is_positive() && y.is_positive() {
-          adjacent_coords.push((x as u32, y as u32));
-        }
-      }
-    }
-
-    adjacent_coords
-  }
+  numbers
 }
 
-fn check_coords(
-  board: &String,
-  coords: Vec<(u32, u32)>,
-  valid_chars: &Vec<char>,
-) -> Vec<(u32, u32)> {
-  let mut valid_coords: Vec<(u32, u32)> = Vec::new();
-
-  coords.iter().for_each(|(x, y)| {
-    let max_y = board.lines().count() as u32;
-    let max_x = board.lines().nth(0).unwrap().chars().count() as u32;
-
-    if *x >= max_x || *y >= max_y {
-      return;
-    }
-
-    let mut char_iter = board.lines().nth(*y as usize).unwrap().chars();
-    let char = char_iter.nth(*x as usize).unwrap();
-
-    if valid_chars.contains(&char) {
-      valid_coords.push((*x, *y));
-    }
-  });
+/// The engine schematic, a rectangular grid of digits, dots and symbols.
+pub fn parse_input(board: &str) -> Result<Grid<char>> {
+  Grid::parse(Day3::NUMBER, board, Some)
+}
 
-  valid_coords
+/// Sums the part numbers, the numbers next to any of the chars in `symbols`.
+pub fn solve_a(board: &Grid<char>, symbols: &str) -> Result<Answer> {
+  let sum = numbers(board)
+    .iter()
+    .filter(|number| {
+      number
+        .adjacent(board)
+        .iter()
+        .any(|coord| symbols.contains(board[*coord]))
+    })
+    .map(|number| number.value)
+    .sum::<u32>();
+
+  Ok(sum.into())
 }
 
-fn get_numbers_for_valid_chars(board: &String, valid_chars: Vec<char>) -> Vec<Number> {
-  let mut numbers: Vec<Number> = Vec::new();
-
-  for (y, line) in board.lines().enumerate() {
-    let mut prev_char: char = '.';
-    let mut current_number: Number = Number {
-      value: 0,
-      position_start: (0, 0),
-      position_end: (0, 0),
-    };
-    for (x, char) in line.chars().enumerate() {
-      if char.is_digit(10) {
-        if prev_char.is_digit(10) {
-          current_number.expand_number(char, (x as u32, y as u32));
-        } else {
-          current_number = Number::new(char, (x as u32, y as u32), (x as u32, y as u32));
-        }
-      }
+/// Sums the gear ratios, the products of the two numbers next to a `*` that
+/// has exactly two.
+pub fn solve_b(board: &Grid<char>) -> Result<Answer> {
+  let mut gears: HashMap<Coord, Vec<u32>> = HashMap::new();
 
-      if !char.is_digit(10)
-        && prev_char.is_digit(10)
-        && check_coords(
-          &board,
-          current_number.generate_adjacent_coords(),
-          &valid_chars.to_vec(),
-        )
-        .len()
-          > 0
-      {
-        numbers.push(current_number);
+  for number in numbers(board) {
+    for coord in number.adjacent(board) {
+      if board[coord] == '*' {
+        gears.entry(coord).or_default().push(number.value);
       }
-
-      if x == line.len() - 1
-        && char.is_digit(10)
-        && check_coords(
-          &board,
-          current_number.generate_adjacent_coords(),
-          &valid_chars.to_vec(),
-        )
-        .len()
-          > 0
-      {
-        numbers.push(current_number);
-      }
-
-      prev_char = char;
     }
   }
 
-  numbers
-}
+  let sum = gears
+    .values()
+    .filter(|values| values.len() == 2)
+    .map(|values| values[0] * values[1])
+    .sum::<u32>();
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let board = fs::read_to_string(input_file_path).unwrap();
-
-  get_numbers_for_valid_chars(
-    &board,
-    ['*', '-', '#', '/', '=', '%', '$', '&', '@', '+'].to_vec(),
-  )
-  .iter()
-  .fold(0, |acc, number| acc + number.value)
-  .try_into()
-  .unwrap()
+  Ok(sum.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  let board = fs::read_to_string(input_file_path).unwrap();
-  let numbers = get_numbers_for_valid_chars(&board, ['*'].to_vec());
+/// The puzzle, see `Day`.
+pub struct Day3;
 
-  // only keep numbers, that have a common * adjacent to them
-  let matching_numbers = numbers.iter().map(|number| {
-    let adjacent_coords = check_coords(&board, number.generate_adjacent_coords(), &['*'].to_vec());
+impl Day for Day3 {
+  type Input = Grid<char>;
 
-    let other_number = numbers.iter().find(|other_number| {
-      if number == *other_number {
-        return false;
-      }
+  const NUMBER: u8 = 3;
+  const TITLE: &'static str = "Gear Ratios";
+  const PARAMS: &'static [Param] = &[Param {
+    name: "symbols",
+    default: "*-#/=%$&@+",
+    description: "symbols that mark part numbers (part a)",
+  }];
+
+  fn parse(input: &str) -> Result<Grid<char>> {
+    parse_input(input)
+  }
 
-      check_coords(
-        &board,
-        other_number.generate_adjacent_coords(),
-        &['*'].to_vec(),
-      )
-      .iter()
-      .any(|coord| adjacent_coords.contains(coord))
-    });
-
-    let other_number_value = match other_number {
-      Some(other_number) => other_number.value,
-      None => 0,
-    };
-
-    (number.value, other_number_value)
-  });
-
-  (matching_numbers.fold(0, |acc, (number_value, other_number_value)| {
-    acc + number_value * other_number_value
-  }) / 2)
-    .try_into()
-    .unwrap()
+  fn part_a(board: &Grid<char>, params: &Params) -> Result<Answer> {
+    solve_a(board, &params.get::<String>("symbols")?)
+  }
+
+  fn part_b(board: &Grid<char>, _: &Params) -> Result<Answer> {
+    solve_b(board)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::error::Error;
+  use crate::input::normalize;
+
+  const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
 
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day3.txt"), 4361);
+    let board = parse_input(EXAMPLE).unwrap();
+
+    assert_eq!(solve_a(&board, "*-#/=%$&@+"), Ok(4361.into()));
+    assert_eq!(solve_a(&board, "*"), Ok(2472.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day3.txt"), 467835);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(467835.into()));
+  }
+
+  #[test]
+  fn test_edge_symbols() {
+    // symbols in the first row and column count, the solver before the grid
+    // port skipped them
+    let board = parse_input("*12.\n....\n#...\n3...").unwrap();
+    assert_eq!(solve_a(&board, "*#"), Ok(15.into()));
+
+    let board = parse_input("2*3\n...").unwrap();
+    assert_eq!(solve_b(&board), Ok(6.into()));
+  }
+
+  #[test]
+  fn test_gear_needs_two_numbers() {
+    // a `*` next to three numbers is no gear, nor is one next to a single one
+    assert_eq!(
+      solve_b(&parse_input(".2.\n1*3\n...").unwrap()),
+      Ok(0.into())
+    );
+    assert_eq!(
+      solve_b(&parse_input(".2.\n.*.\n...").unwrap()),
+      Ok(0.into())
+    );
+  }
+
+  #[test]
+  fn test_ragged_board() {
+    match parse_input("467..\n...*.\n..3") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (3, 4)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_crlf_input() {
+    let crlf = format!("\u{feff}{}\r\n\r\n", EXAMPLE.replace('\n', "\r\n"));
+
+    assert_eq!(
+      solve_a(&parse_input(&normalize(&crlf)).unwrap(), "*-#/=%$&@+"),
+      Ok(4361.into())
+    );
   }
 }
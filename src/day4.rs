@@ -1,69 +1,93 @@
+//! Day 4: Scratchcards, counting the winning numbers on each card.
+
 use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
 
-fn parse_input(input: &str) -> (u32, Vec<u32>, Vec<u32>) {
-  let binding = input;
-  let (id, game) = binding.split_once(": ").unwrap();
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::Params;
 
-  let (numbers, guesses) = game.split_once("|").unwrap();
+/// A card id with its winning numbers and the numbers you have.
+pub type Card = (u32, Vec<u32>, Vec<u32>);
 
-  (
-    id.split_whitespace()
-      .filter_map(|n| n.parse::<u32>().ok())
-      .last()
-      .unwrap(),
-    numbers
-      .split_whitespace()
-      .filter_map(|n| n.parse::<u32>().ok())
-      .collect::<Vec<u32>>(),
-    guesses
+fn parse_card(index: usize, line: &str) -> Result<Card> {
+  let error = |token: &str, message: &str| Error::parse(Day4::NUMBER, index, line, token, message);
+  let numbers = |list: &str| {
+    list
       .split_whitespace()
-      .filter_map(|g| g.parse::<u32>().ok())
-      .collect::<Vec<u32>>(),
-  )
+      .map(|n| parse_number::<u32>(Day4::NUMBER, index, line, n))
+      .collect::<Result<Vec<u32>>>()
+  };
+
+  let (id, game) = line
+    .split_once(": ")
+    .ok_or_else(|| error(line, "expected `Card <id>: <numbers> | <guesses>`"))?;
+
+  let (winning, guesses) = game
+    .split_once('|')
+    .ok_or_else(|| error(game, "expected `<numbers> | <guesses>`"))?;
+
+  let id = id
+    .split_whitespace()
+    .next_back()
+    .ok_or_else(|| error(id, "missing card id"))?;
+
+  Ok((
+    parse_number(Day4::NUMBER, index, line, id)?,
+    numbers(winning)?,
+    numbers(guesses)?,
+  ))
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
+/// One card per line, `Card <id>: <winning numbers> | <numbers you have>`.
+pub fn parse_input(input: &str) -> Result<Vec<Card>> {
+  input
     .lines()
-    .map(|line| parse_input(&line.unwrap()))
+    .enumerate()
+    .map(|(index, line)| parse_card(index, line))
+    .collect()
+}
+
+/// Sums the points of the cards, 1 for the first match and doubling for each
+/// one after it.
+pub fn solve_a(cards: &[Card]) -> Result<Answer> {
+  let sum = cards
+    .iter()
     .map(|(_, numbers, guesses)| {
       numbers
         .iter()
         .map(|n| guesses.iter().any(|g| g == n) as i32)
-        .map(|b| b as i32)
         .sum::<i32>()
     })
     .map(|count| match count {
       0 => 0,
       x => i32::pow(2, (x - 1) as u32) as u64,
     })
-    .sum()
+    .sum::<u64>();
+
+  Ok(sum.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  BufReader::new(fs::File::open(input_file_path).unwrap())
-    .lines()
-    .map(|line| parse_input(&line.unwrap()))
+/// Counts the cards you end up with, as every match wins a copy of one of the
+/// cards after it.
+pub fn solve_b(cards: &[Card]) -> Result<Answer> {
+  let sum = cards
+    .iter()
     .map(|(id, numbers, guesses)| {
       (
-        id,
+        *id,
         numbers
           .iter()
           .map(|n| guesses.iter().any(|g| g == n) as i32)
-          .map(|b| b as i32)
           .sum::<i32>(),
       )
     })
     .fold(HashMap::new(), |mut acc, (id, count)| {
-      if acc.get(&(id as i32)).is_none() {
-        acc.insert(id as i32, 1);
-      }
+      acc.entry(id as i32).or_insert(1);
 
       ((id as i32) + 1..=(count + (id as i32))).for_each(|i| {
         let entry = *acc.clone().get(&(i)).unwrap_or(&1);
-        let id_copy = id.clone() as i32;
+        let id_copy = id as i32;
         let times = *acc.clone().get(&id_copy).unwrap_or(&1);
         acc.insert(
           i,
@@ -77,22 +101,60 @@ pub fn solve_b(input_file_path: &str) -> u64 {
       acc
     })
     .values()
-    .sum::<u32>()
-    .try_into()
-    .unwrap()
+    .sum::<u32>();
+
+  Ok(sum.into())
+}
+
+/// The puzzle, see `Day`.
+pub struct Day4;
+
+impl Day for Day4 {
+  type Input = Vec<Card>;
+
+  const NUMBER: u8 = 4;
+  const TITLE: &'static str = "Scratchcards";
+
+  fn parse(input: &str) -> Result<Vec<Card>> {
+    parse_input(input)
+  }
+
+  fn part_a(cards: &Vec<Card>, _: &Params) -> Result<Answer> {
+    solve_a(cards)
+  }
+
+  fn part_b(cards: &Vec<Card>, _: &Params) -> Result<Answer> {
+    solve_b(cards)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day4.txt"), 13);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(13.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day4.txt"), 30);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(30.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("Card 1: 41 48 | 83 86\nCard 2: 13 3x | 61") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 12)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
   }
 }
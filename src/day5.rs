@@ -1,27 +1,108 @@
-use rayon::prelude::*;
-use std::fs;
+//! Day 5: If You Give A Seed A Fertilizer, seeds mapped through an almanac.
 
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::Params;
+
+/// One `<destination> <source> <length>` line of an almanac map.
 #[derive(Debug)]
-struct Range {
-  destination: i64,
-  source: i64,
-  range: i64,
+pub struct Range {
+  /// Where the range starts after the map.
+  pub destination: i64,
+  /// Where the range starts before the map.
+  pub source: i64,
+  /// How many numbers it covers.
+  pub range: i64,
+}
+
+/// One almanac map, e.g. `seed-to-soil`. Numbers outside all of its ranges
+/// map to themselves.
+#[derive(Debug, Default)]
+pub struct SeedMap {
+  /// The ranges, in the order the almanac lists them.
+  pub map: Vec<Range>,
 }
 
+/// All maps of the almanac, in the order a seed goes through them.
 #[derive(Debug)]
-struct SeedMap {
-  map: Vec<Range>,
+pub struct SeedMaps {
+  /// `seed-to-soil map:`
+  pub seed_to_soil: SeedMap,
+  /// `soil-to-fertilizer map:`
+  pub soil_to_fertilizer: SeedMap,
+  /// `fertilizer-to-water map:`
+  pub fertilizer_to_water: SeedMap,
+  /// `water-to-light map:`
+  pub water_to_light: SeedMap,
+  /// `light-to-temperature map:`
+  pub light_to_temperature: SeedMap,
+  /// `temperature-to-humidity map:`
+  pub temperature_to_humidity: SeedMap,
+  /// `humidity-to-location map:`
+  pub humidity_to_location: SeedMap,
 }
 
+/// `<start> <length>` of the seeds line, read two numbers at a time in part b.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedRange {
+  /// The first seed.
+  pub start: i64,
+  /// How many seeds there are.
+  pub length: i64,
+}
+
+impl SeedRange {
+  /// Pairs up `seeds` by position, an odd count leaves a start without a
+  /// length.
+  pub fn from_seeds(seeds: &[i64]) -> Result<Vec<SeedRange>> {
+    if seeds.len() % 2 == 1 {
+      return Err(Error::unsolvable(
+        Day5::NUMBER,
+        format!(
+          "{} seed numbers do not pair up as `<start> <length>`",
+          seeds.len()
+        ),
+      ));
+    }
+
+    Ok(
+      seeds
+        .chunks(2)
+        .map(|pair| SeedRange {
+          start: pair[0],
+          length: pair[1],
+        })
+        .collect(),
+    )
+  }
+
+  /// The seeds as a `[start, end)` interval, an error when `end` does not
+  /// fit in an `i64`.
+  pub fn interval(&self) -> Result<(i64, i64)> {
+    let end = self.start.checked_add(self.length).ok_or_else(|| {
+      Error::unsolvable(
+        Day5::NUMBER,
+        format!(
+          "{} seeds from {} go past {}",
+          self.length,
+          self.start,
+          i64::MAX
+        ),
+      )
+    })?;
+
+    Ok((self.start, end))
+  }
+}
+
+/// The parsed almanac: the seeds line and the maps.
 #[derive(Debug)]
-struct SeedMaps {
-  seed_to_soil: SeedMap,
-  soil_to_fertilizer: SeedMap,
-  fertilizer_to_water: SeedMap,
-  water_to_light: SeedMap,
-  light_to_temperature: SeedMap,
-  temperature_to_humidity: SeedMap,
-  humidity_to_location: SeedMap,
+pub struct Almanac {
+  /// The numbers on the `seeds:` line.
+  pub seeds: Vec<i64>,
+  /// The maps the seeds go through.
+  pub maps: SeedMaps,
 }
 
 impl SeedMaps {
@@ -37,29 +118,46 @@ impl SeedMaps {
     }
   }
 
-  fn solve(&self, source: i64) -> i64 {
-    self.humidity_to_location.find_in_map(
-      self.temperature_to_humidity.find_in_map(
-        self.light_to_temperature.find_in_map(
-          self.water_to_light.find_in_map(
-            self.fertilizer_to_water.find_in_map(
-              self
-                .soil_to_fertilizer
-                .find_in_map(self.seed_to_soil.find_in_map(source)),
-            ),
-          ),
-        ),
-      ),
-    )
+  fn in_order(&self) -> [&SeedMap; 7] {
+    [
+      &self.seed_to_soil,
+      &self.soil_to_fertilizer,
+      &self.fertilizer_to_water,
+      &self.water_to_light,
+      &self.light_to_temperature,
+      &self.temperature_to_humidity,
+      &self.humidity_to_location,
+    ]
+  }
+
+  /// The location `source` ends up at after going through every map.
+  pub fn solve(&self, source: i64) -> i64 {
+    self
+      .in_order()
+      .iter()
+      .fold(source, |source, map| map.find_in_map(source))
+  }
+
+  /// The location intervals the `[start, end)` seed `intervals` end up at
+  /// after going through every map, in no particular order.
+  pub fn solve_intervals(&self, intervals: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    self
+      .in_order()
+      .iter()
+      .fold(intervals.to_vec(), |intervals, map| {
+        map.map_intervals(&intervals)
+      })
   }
 }
 
 impl SeedMap {
-  fn new() -> SeedMap {
+  /// A map without ranges, every number maps to itself.
+  pub fn new() -> SeedMap {
     SeedMap { map: Vec::new() }
   }
 
-  fn find_in_map(&self, source: i64) -> i64 {
+  /// Maps `source` to its destination.
+  pub fn find_in_map(&self, source: i64) -> i64 {
     self
       .map
       .iter()
@@ -72,43 +170,109 @@ impl SeedMap {
           if range.source <= x && x < range.source + range.range {
             return Some(range.destination - range.source + x);
           }
-          return None;
+          None
         })
       })
       .unwrap_or(source)
   }
+
+  /// Maps whole `[start, end)` intervals at once, splitting them where the
+  /// ranges start and end. Like `find_in_map`, the first range that covers a
+  /// number wins.
+  pub fn map_intervals(&self, intervals: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut pending = intervals.to_vec();
+    let mut mapped = vec![];
+
+    for range in &self.map {
+      let (source_start, source_end) = (range.source, range.source + range.range);
+      let shift = range.destination - range.source;
+      let mut rest = vec![];
+
+      for (start, end) in pending {
+        let before = (start, end.min(source_start));
+        let inside = (start.max(source_start), end.min(source_end));
+        let after = (start.max(source_end), end);
+
+        if before.0 < before.1 {
+          rest.push(before);
+        }
+        if inside.0 < inside.1 {
+          mapped.push((inside.0 + shift, inside.1 + shift));
+        }
+        if after.0 < after.1 {
+          rest.push(after);
+        }
+      }
+
+      pending = rest;
+    }
+
+    mapped.extend(pending);
+    mapped
+  }
 }
 
-fn parse_input(input: &str) -> (Vec<i64>, SeedMaps) {
+/// The `seeds:` line, then one block per map.
+pub fn parse_input(input: &str) -> Result<Almanac> {
   let mut seed_map = SeedMaps::new();
 
   let groups = input.split("\n\n").collect::<Vec<&str>>();
-  let seed = groups[0]
+  let seed_line = groups[0].lines().next().unwrap_or_default();
+  let seed = seed_line
     .split_once(": ")
-    .unwrap()
+    .ok_or_else(|| {
+      Error::parse(
+        Day5::NUMBER,
+        0,
+        seed_line,
+        seed_line,
+        "expected `seeds: <numbers>`",
+      )
+    })?
     .1
     .split_whitespace()
-    .filter_map(|x| x.parse::<i64>().ok())
-    .collect::<Vec<i64>>();
+    .map(|x| parse_number::<i64>(Day5::NUMBER, 0, seed_line, x))
+    .collect::<Result<Vec<i64>>>()?;
+
+  // index of the first line of the current group, for error positions
+  let mut first_line = groups[0].split('\n').count() + 1;
 
   for group in groups[1..].iter() {
-    let mut lines = group.lines();
-    let category = lines.next().unwrap();
+    let offset = first_line;
+    let mut lines = group
+      .lines()
+      .enumerate()
+      .map(|(i, line)| (offset + i, line));
+    first_line += group.split('\n').count() + 1;
+
+    let (category_index, category) = match lines.next() {
+      Some(category) => category,
+      None => continue,
+    };
 
     let ranges: Vec<Range> = lines
-      .map(|line| {
-        let mut parts = line.split_whitespace();
-        let destination = parts.next().unwrap().parse::<i64>().unwrap();
-        let source = parts.next().unwrap().parse::<i64>().unwrap();
-        let range = parts.next().unwrap().parse::<i64>().unwrap();
-
-        Range {
-          destination,
-          source,
-          range,
+      .map(|(index, line)| {
+        let numbers = line
+          .split_whitespace()
+          .map(|x| parse_number::<i64>(Day5::NUMBER, index, line, x))
+          .collect::<Result<Vec<i64>>>()?;
+
+        match numbers[..] {
+          [destination, source, range] => Ok(Range {
+            destination,
+            source,
+            range,
+          }),
+          _ => Err(Error::parse(
+            Day5::NUMBER,
+            index,
+            line,
+            line,
+            "expected `<destination> <source> <length>`",
+          )),
         }
       })
-      .collect::<Vec<Range>>();
+      .collect::<Result<Vec<Range>>>()?;
 
     match category {
       "seed-to-soil map:" => {
@@ -132,59 +296,246 @@ fn parse_input(input: &str) -> (Vec<i64>, SeedMaps) {
       "humidity-to-location map:" => {
         seed_map.humidity_to_location.map = ranges;
       }
-      _ => {}
+      other => {
+        return Err(Error::parse(
+          Day5::NUMBER,
+          category_index,
+          other,
+          other,
+          "unknown map",
+        ))
+      }
     }
   }
 
-  (seed, seed_map)
+  Ok(Almanac {
+    seeds: seed,
+    maps: seed_map,
+  })
+}
+
+fn to_answer(location: Option<i64>) -> Result<Answer> {
+  location
+    .map(Answer::from)
+    .ok_or_else(|| Error::unsolvable(Day5::NUMBER, "no seeds to plant"))
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let input = fs::read_to_string(input_file_path).unwrap();
-  let (seeds, seed_map) = parse_input(&input);
+/// The lowest location of any of the seeds.
+pub fn solve_a(almanac: &Almanac) -> Result<Answer> {
+  let Almanac { seeds, maps } = almanac;
 
-  seeds
-    .iter()
-    .map(|seed| seed_map.solve(*seed))
-    .min()
-    .unwrap()
-    .try_into()
-    .unwrap()
+  to_answer(seeds.iter().map(|seed| maps.solve(*seed)).min())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  let input = fs::read_to_string(input_file_path).unwrap();
-  let (seeds, seed_map) = parse_input(&input);
+/// The lowest location of any seed in the `<start> <length>` ranges of the
+/// seeds line.
+pub fn solve_b(almanac: &Almanac) -> Result<Answer> {
+  let Almanac { seeds, maps } = almanac;
 
-  seeds
+  let intervals = SeedRange::from_seeds(seeds)?
     .iter()
-    .zip(seeds.iter().skip(1))
-    .filter(|(i, _)| *i % 2 == 1)
-    .collect::<Vec<_>>()
-    .par_iter()
-    .map(|(i, j)| {
-      (**i..**i + **j)
-        .map(|seed| seed_map.solve(seed))
-        .min()
-        .unwrap()
-    })
-    .min()
-    .unwrap()
-    .try_into()
-    .unwrap()
+    .map(SeedRange::interval)
+    .collect::<Result<Vec<(i64, i64)>>>()?;
+
+  to_answer(
+    maps
+      .solve_intervals(&intervals)
+      .iter()
+      .map(|(start, _)| *start)
+      .min(),
+  )
+}
+
+/// The puzzle, see `Day`.
+pub struct Day5;
+
+impl Day for Day5 {
+  type Input = Almanac;
+
+  const NUMBER: u8 = 5;
+  const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+  fn parse(input: &str) -> Result<Almanac> {
+    parse_input(input)
+  }
+
+  fn part_a(almanac: &Almanac, _: &Params) -> Result<Answer> {
+    solve_a(almanac)
+  }
+
+  fn part_b(almanac: &Almanac, _: &Params) -> Result<Answer> {
+    solve_b(almanac)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::input::normalize;
+  use crate::test_util::XorShift;
+
+  const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
 
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day5.txt"), 35);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(35.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day5.txt"), 46);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(46.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    let input = EXAMPLE.replace("37 52 2", "37 52");
+
+    match parse_input(&input) {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (9, 1)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_crlf_input() {
+    let crlf = format!("\u{feff}{}\r\n\r\n", EXAMPLE.replace('\n', "\r\n"));
+
+    assert_eq!(
+      solve_a(&parse_input(&normalize(&crlf)).unwrap()),
+      Ok(35.into())
+    );
+  }
+
+  /// The lowest location of any seed in `intervals`, one seed at a time.
+  fn brute_force(maps: &SeedMaps, intervals: &[(i64, i64)]) -> Option<i64> {
+    intervals
+      .iter()
+      .flat_map(|(start, end)| *start..*end)
+      .map(|seed| maps.solve(seed))
+      .min()
+  }
+
+  #[test]
+  fn test_solve_intervals_brute_force() {
+    let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+    let mut random = |bound: u64| rng.below(bound) as i64;
+
+    let mut almanac = parse_input(EXAMPLE).unwrap();
+    for round in 0..50 {
+      if round > 0 {
+        for map in [
+          &mut almanac.maps.seed_to_soil,
+          &mut almanac.maps.soil_to_fertilizer,
+          &mut almanac.maps.fertilizer_to_water,
+          &mut almanac.maps.water_to_light,
+          &mut almanac.maps.light_to_temperature,
+          &mut almanac.maps.temperature_to_humidity,
+          &mut almanac.maps.humidity_to_location,
+        ] {
+          map.map = (0..random(5))
+            .map(|_| Range {
+              destination: random(200),
+              source: random(200),
+              range: random(50) + 1,
+            })
+            .collect();
+        }
+      }
+
+      let intervals = (0..random(4) + 1)
+        .map(|_| {
+          let start = random(200);
+          (start, start + random(40) + 1)
+        })
+        .collect::<Vec<(i64, i64)>>();
+
+      let locations = almanac.maps.solve_intervals(&intervals);
+      assert_eq!(
+        locations.iter().map(|(start, _)| *start).min(),
+        brute_force(&almanac.maps, &intervals),
+        "{:?} through {:?}",
+        intervals,
+        almanac.maps
+      );
+      assert_eq!(
+        locations
+          .iter()
+          .map(|(start, end)| end - start)
+          .sum::<i64>(),
+        intervals
+          .iter()
+          .map(|(start, end)| end - start)
+          .sum::<i64>()
+      );
+    }
+  }
+
+  #[test]
+  fn test_seed_ranges() {
+    // even starts, which pairing on odd seed values used to drop
+    let mut almanac = parse_input(&EXAMPLE.replacen("79 14 55 13", "78 14 54 13", 1)).unwrap();
+    let ranges = SeedRange::from_seeds(&almanac.seeds).unwrap();
+    assert_eq!(
+      ranges,
+      vec![
+        SeedRange {
+          start: 78,
+          length: 14
+        },
+        SeedRange {
+          start: 54,
+          length: 13
+        }
+      ]
+    );
+
+    let intervals = ranges
+      .iter()
+      .map(SeedRange::interval)
+      .collect::<Result<Vec<(i64, i64)>>>()
+      .unwrap();
+    let expected = brute_force(&almanac.maps, &intervals).unwrap();
+    assert_eq!(solve_b(&almanac), Ok(Answer::from(expected)));
+
+    almanac.seeds = vec![i64::MAX - 7, 100];
+    assert!(matches!(solve_b(&almanac), Err(Error::Unsolvable { .. })));
+
+    almanac.seeds = vec![78, 14, 54, 13, 7];
+    assert!(matches!(solve_b(&almanac), Err(Error::Unsolvable { .. })));
+    // part a takes any number of seeds
+    assert!(solve_a(&almanac).is_ok());
   }
 }
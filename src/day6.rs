@@ -1,97 +1,224 @@
-use std::fs;
+//! Day 6: Wait For It, boat races won by holding the button long enough.
 
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::Params;
+
+/// One race: its duration and the record distance to beat.
 #[derive(Debug)]
-struct HighScore {
-  time: i64,
-  distance: i64,
+pub struct HighScore {
+  /// How long the race lasts, in milliseconds.
+  pub time: u64,
+  /// The record distance, in millimetres.
+  pub distance: u64,
 }
 
-fn parse_input(input: &str) -> Vec<HighScore> {
-  let times = input
-    .lines()
-    .nth(0)
-    .unwrap()
+fn parse_row(index: usize, line: Option<&str>, label: &str) -> Result<Vec<u64>> {
+  let line = line.unwrap_or_default();
+  let numbers = line.strip_prefix(label).ok_or_else(|| {
+    Error::parse(
+      Day6::NUMBER,
+      index,
+      line,
+      line,
+      format!("expected `{} <numbers>`", label),
+    )
+  })?;
+
+  numbers
     .split_whitespace()
-    .filter_map(|num| num.parse::<i64>().ok())
-    .collect::<Vec<i64>>();
+    .map(|num| parse_number::<u64>(Day6::NUMBER, index, line, num))
+    .collect()
+}
 
-  let distances = input
-    .lines()
-    .nth(1)
-    .unwrap()
-    .split_whitespace()
-    .filter_map(|num| num.parse::<i64>().ok())
-    .collect::<Vec<i64>>();
+/// The `Time:` and `Distance:` lines, one column per race.
+pub fn parse_input(input: &str) -> Result<Vec<HighScore>> {
+  let mut lines = input.lines();
+  let times = parse_row(0, lines.next(), "Time:")?;
+  let distance_line = lines.next();
+  let distances = parse_row(1, distance_line, "Distance:")?;
+
+  if times.len() != distances.len() {
+    let line = distance_line.unwrap_or_default();
+    return Err(Error::parse(
+      Day6::NUMBER,
+      1,
+      line,
+      line,
+      format!(
+        "expected {} distances, found {}",
+        times.len(),
+        distances.len()
+      ),
+    ));
+  }
 
-  times
-    .iter()
-    .zip(distances.iter())
-    .map(|(time, distance)| HighScore {
-      time: *time,
-      distance: *distance,
-    })
-    .collect::<Vec<HighScore>>()
+  Ok(
+    times
+      .iter()
+      .zip(distances.iter())
+      .map(|(time, distance)| HighScore {
+        time: *time,
+        distance: *distance,
+      })
+      .collect::<Vec<HighScore>>(),
+  )
+}
+
+/// How many hold times beat `distance` in a race of `time`, `None` when the
+/// numbers are too big to square. Holding for `h` goes `h * (time - h)`, so
+/// the winners lie strictly between the roots of `h^2 - time * h + distance`,
+/// which an integer square root finds exactly.
+pub fn ways_to_win(time: u128, distance: u128) -> Option<u128> {
+  let wins = |hold: u128| hold * (time - hold) > distance;
+
+  let discriminant = match time
+    .checked_mul(time)?
+    .checked_sub(distance.checked_mul(4)?)
+  {
+    Some(discriminant) => discriminant,
+    None => return Some(0),
+  };
+
+  // the isqrt is exact, only the halving can leave `first` one off
+  let mut first = (time - discriminant.isqrt()) / 2;
+  while first <= time / 2 && !wins(first) {
+    first += 1;
+  }
+  while first > 0 && wins(first - 1) {
+    first -= 1;
+  }
+
+  // winners are symmetric around time / 2
+  Some(match first <= time / 2 {
+    true => time - 2 * first + 1,
+    false => 0,
+  })
 }
 
-fn solve_quadratic(a: i64, b: i64, c: i64) -> (f64, f64) {
-  let discriminant = b.pow(2) - 4 * a * c;
-  let sqrt_discriminant = (discriminant as f64).sqrt();
+fn count_ways(time: u128, distance: u128) -> Result<u128> {
+  ways_to_win(time, distance)
+    .ok_or_else(|| Error::unsolvable(Day6::NUMBER, "race is too long to solve exactly"))
+}
 
-  let x1 = (-b as f64 + sqrt_discriminant) / (2.0 * a as f64);
-  let x2 = (-b as f64 - sqrt_discriminant) / (2.0 * a as f64);
+/// Multiplies the number of ways to win each race.
+pub fn solve_a(high_scores: &[HighScore]) -> Result<Answer> {
+  let product = high_scores.iter().try_fold(1u128, |product, high_score| {
+    let ways = count_ways(high_score.time as u128, high_score.distance as u128)?;
+    product
+      .checked_mul(ways)
+      .ok_or_else(|| Error::unsolvable(Day6::NUMBER, "product of the ways overflows"))
+  })?;
 
-  (x1, x2)
+  Ok(product.into())
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let high_scores = parse_input(&fs::read_to_string(input_file_path).unwrap());
+/// The number of ways to win the one race the columns make when their
+/// digits are read as a single number.
+pub fn solve_b(high_scores: &[HighScore]) -> Result<Answer> {
+  let concat = |acc: u128, value: u64| {
+    (acc.to_string() + &value.to_string())
+      .parse::<u128>()
+      .map_err(|_| Error::unsolvable(Day6::NUMBER, "concatenated race is too long"))
+  };
 
-  high_scores
+  let (time, distance) = high_scores
     .iter()
-    .map(|high_score| {
-      let (x1, x2) = solve_quadratic(-1, high_score.time, -high_score.distance - 1);
-
-      ((x1.ceil() - x2.floor()).abs() + 1.0) as u32
-    })
-    .fold(1, |acc, count| acc * count)
-    .try_into()
-    .unwrap()
+    .try_fold((0, 0), |(time, distance), high_score| {
+      Ok((
+        concat(time, high_score.time)?,
+        concat(distance, high_score.distance)?,
+      ))
+    })?;
+
+  Ok(count_ways(time, distance)?.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  let high_score = parse_input(&fs::read_to_string(input_file_path).unwrap())
-    .iter()
-    .fold(
-      HighScore {
-        time: 0,
-        distance: 0,
-      },
-      |acc, high_score| HighScore {
-        time: (acc.time.to_string() + &high_score.time.to_string())
-          .parse::<i64>()
-          .unwrap(),
-        distance: (acc.distance.to_string() + &high_score.distance.to_string())
-          .parse::<i64>()
-          .unwrap(),
-      },
-    );
-
-  let (x1, x2) = solve_quadratic(-1, high_score.time, -high_score.distance - 1);
-
-  ((x1.ceil() - x2.floor()).abs() + 1.0) as u64
+/// The puzzle, see `Day`.
+pub struct Day6;
+
+impl Day for Day6 {
+  type Input = Vec<HighScore>;
+
+  const NUMBER: u8 = 6;
+  const TITLE: &'static str = "Wait For It";
+
+  fn parse(input: &str) -> Result<Vec<HighScore>> {
+    parse_input(input)
+  }
+
+  fn part_a(high_scores: &Vec<HighScore>, _: &Params) -> Result<Answer> {
+    solve_a(high_scores)
+  }
+
+  fn part_b(high_scores: &Vec<HighScore>, _: &Params) -> Result<Answer> {
+    solve_b(high_scores)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::test_util::XorShift;
+
+  const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
 
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day6.txt"), 288);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(288.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day6.txt"), 71503);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(71503.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("Time:      7  15   30\nDistance:  9  40") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 1)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  /// Counts the winning hold times one by one.
+  fn brute_force(time: u128, distance: u128) -> u128 {
+    (0..=time)
+      .filter(|hold| hold * (time - hold) > distance)
+      .count() as u128
+  }
+
+  #[test]
+  fn test_ways_to_win_brute_force() {
+    let mut rng = XorShift::new(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2000 {
+      let time = rng.below(300) as u128;
+      let distance = rng.below((time * time / 4 + 3) as u64) as u128;
+
+      assert_eq!(
+        ways_to_win(time, distance),
+        Some(brute_force(time, distance)),
+        "time {}, distance {}",
+        time,
+        distance
+      );
+    }
+  }
+
+  #[test]
+  fn test_ways_to_win_large() {
+    // past 2^53 an f64 square root can no longer tell these apart
+    let time = (1u128 << 60) + 7;
+    let distance = time * time / 4 - 12345;
+    let ways = ways_to_win(time, distance).unwrap();
+    let first = (time + 1 - ways) / 2;
+
+    assert!(first * (time - first) > distance);
+    assert!((first - 1) * (time - first + 1) <= distance);
+    assert_eq!(ways_to_win(u128::MAX, 1), None);
   }
 }
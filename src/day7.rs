@@ -1,9 +1,15 @@
+//! Day 7: Camel Cards, ranking poker-like hands by their bids.
+
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
+use std::fmt;
 use std::ops::Deref;
 
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::Params;
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 enum Card {
   WithoutJoker(CardWithoutJoker),
@@ -11,20 +17,20 @@ enum Card {
 }
 
 impl Card {
-  fn parse(c: char, joker: bool) -> Card {
+  fn parse(c: char, joker: bool) -> Option<Card> {
     if joker {
-      Card::WithJoker(CardWithJoker::parse(c))
+      CardWithJoker::parse(c).map(Card::WithJoker)
     } else {
-      Card::WithoutJoker(CardWithoutJoker::parse(c))
+      CardWithoutJoker::parse(c).map(Card::WithoutJoker)
     }
   }
 }
 
-impl ToString for Card {
-  fn to_string(&self) -> String {
+impl fmt::Display for Card {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      Card::WithoutJoker(c) => c.to_string(),
-      Card::WithJoker(c) => c.to_string(),
+      Card::WithoutJoker(c) => c.fmt(f),
+      Card::WithJoker(c) => c.fmt(f),
     }
   }
 }
@@ -64,8 +70,8 @@ enum CardWithJoker {
 }
 
 impl CardWithoutJoker {
-  fn parse(c: char) -> CardWithoutJoker {
-    match c {
+  fn parse(c: char) -> Option<CardWithoutJoker> {
+    let card = match c {
       'A' => CardWithoutJoker::Ace,
       'K' => CardWithoutJoker::King,
       'Q' => CardWithoutJoker::Queen,
@@ -79,34 +85,36 @@ impl CardWithoutJoker {
       '4' => CardWithoutJoker::Four,
       '3' => CardWithoutJoker::Three,
       '2' => CardWithoutJoker::Two,
-      x => panic!("Invalid card: {}", x),
-    }
+      _ => return None,
+    };
+
+    Some(card)
   }
 }
 
-impl ToString for CardWithoutJoker {
-  fn to_string(&self) -> String {
+impl fmt::Display for CardWithoutJoker {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      CardWithoutJoker::Ace => "A".to_string(),
-      CardWithoutJoker::King => "K".to_string(),
-      CardWithoutJoker::Queen => "Q".to_string(),
-      CardWithoutJoker::Jack => "J".to_string(),
-      CardWithoutJoker::Ten => "T".to_string(),
-      CardWithoutJoker::Nine => "9".to_string(),
-      CardWithoutJoker::Eight => "8".to_string(),
-      CardWithoutJoker::Seven => "7".to_string(),
-      CardWithoutJoker::Six => "6".to_string(),
-      CardWithoutJoker::Five => "5".to_string(),
-      CardWithoutJoker::Four => "4".to_string(),
-      CardWithoutJoker::Three => "3".to_string(),
-      CardWithoutJoker::Two => "2".to_string(),
+      CardWithoutJoker::Ace => write!(f, "A"),
+      CardWithoutJoker::King => write!(f, "K"),
+      CardWithoutJoker::Queen => write!(f, "Q"),
+      CardWithoutJoker::Jack => write!(f, "J"),
+      CardWithoutJoker::Ten => write!(f, "T"),
+      CardWithoutJoker::Nine => write!(f, "9"),
+      CardWithoutJoker::Eight => write!(f, "8"),
+      CardWithoutJoker::Seven => write!(f, "7"),
+      CardWithoutJoker::Six => write!(f, "6"),
+      CardWithoutJoker::Five => write!(f, "5"),
+      CardWithoutJoker::Four => write!(f, "4"),
+      CardWithoutJoker::Three => write!(f, "3"),
+      CardWithoutJoker::Two => write!(f, "2"),
     }
   }
 }
 
 impl CardWithJoker {
-  fn parse(c: char) -> CardWithJoker {
-    match c {
+  fn parse(c: char) -> Option<CardWithJoker> {
+    let card = match c {
       'A' => CardWithJoker::Ace,
       'K' => CardWithJoker::King,
       'Q' => CardWithJoker::Queen,
@@ -120,27 +128,29 @@ impl CardWithJoker {
       '3' => CardWithJoker::Three,
       '2' => CardWithJoker::Two,
       'J' => CardWithJoker::Joker,
-      x => panic!("Invalid card: {}", x),
-    }
+      _ => return None,
+    };
+
+    Some(card)
   }
 }
 
-impl ToString for CardWithJoker {
-  fn to_string(&self) -> String {
+impl fmt::Display for CardWithJoker {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      CardWithJoker::Ace => "A".to_string(),
-      CardWithJoker::King => "K".to_string(),
-      CardWithJoker::Queen => "Q".to_string(),
-      CardWithJoker::Ten => "T".to_string(),
-      CardWithJoker::Nine => "9".to_string(),
-      CardWithJoker::Eight => "8".to_string(),
-      CardWithJoker::Seven => "7".to_string(),
-      CardWithJoker::Six => "6".to_string(),
-      CardWithJoker::Five => "5".to_string(),
-      CardWithJoker::Four => "4".to_string(),
-      CardWithJoker::Three => "3".to_string(),
-      CardWithJoker::Two => "2".to_string(),
-      CardWithJoker::Joker => "J".to_string(),
+      CardWithJoker::Ace => write!(f, "A"),
+      CardWithJoker::King => write!(f, "K"),
+      CardWithJoker::Queen => write!(f, "Q"),
+      CardWithJoker::Ten => write!(f, "T"),
+      CardWithJoker::Nine => write!(f, "9"),
+      CardWithJoker::Eight => write!(f, "8"),
+      CardWithJoker::Seven => write!(f, "7"),
+      CardWithJoker::Six => write!(f, "6"),
+      CardWithJoker::Five => write!(f, "5"),
+      CardWithJoker::Four => write!(f, "4"),
+      CardWithJoker::Three => write!(f, "3"),
+      CardWithJoker::Two => write!(f, "2"),
+      CardWithJoker::Joker => write!(f, "J"),
     }
   }
 }
@@ -191,7 +201,7 @@ impl HandValue {
     }
 
     let mut counts = counts.into_iter().collect::<Vec<(Card, u32)>>();
-    counts.sort_by(|a, b| b.1.cmp(&a.1));
+    counts.sort_by_key(|c| std::cmp::Reverse(c.1));
     match counts[0].1 {
       5 => HandValue::FiveOfAKind,
       4 => HandValue::FourOfAKind,
@@ -257,7 +267,7 @@ impl Deref for Cards {
 }
 
 impl Cards {
-  fn iter(&self) -> std::slice::Iter<Card> {
+  fn iter(&self) -> std::slice::Iter<'_, Card> {
     self.0.iter()
   }
 
@@ -274,10 +284,10 @@ struct Hand {
 }
 
 impl Hand {
-  fn new(cards: &Vec<Card>, bid: u32, joker: bool) -> Hand {
+  fn new(cards: &[Card], bid: u32, joker: bool) -> Hand {
     Hand {
-      cards: Cards(cards.clone()),
-      value: HandValue::new(cards.clone(), joker),
+      cards: Cards(cards.to_vec()),
+      value: HandValue::new(cards.to_vec(), joker),
       bid,
     }
   }
@@ -315,22 +325,60 @@ impl Ord for Hand {
   }
 }
 
-fn parse_input(input: &str, joker: bool) -> Hand {
-  let (cards, bid) = input.split_once(" ").unwrap();
+/// A hand as dealt, before it is known whether `J` is a jack or a joker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+  /// The five cards, in the order they were dealt.
+  pub cards: Vec<char>,
+  /// What the hand wins times its rank.
+  pub bid: u32,
+}
+
+fn parse_round(index: usize, line: &str) -> Result<Round> {
+  let error = |token: &str, message: &str| Error::parse(Day7::NUMBER, index, line, token, message);
+
+  let (cards, bid) = line
+    .split_once(' ')
+    .ok_or_else(|| error(line, "expected `<cards> <bid>`"))?;
+
   let cards = cards
-    .chars()
-    .map(|c| Card::parse(c, joker))
-    .collect::<Vec<Card>>();
+    .char_indices()
+    .map(|(i, c)| match Card::parse(c, false) {
+      Some(_) => Ok(c),
+      None => Err(error(&cards[i..], &format!("invalid card `{}`", c))),
+    })
+    .collect::<Result<Vec<char>>>()?;
+
+  if cards.len() != 5 {
+    return Err(error(line, "a hand needs exactly 5 cards"));
+  }
 
-  let bid = bid.parse::<u32>().unwrap();
+  let bid = parse_number::<u32>(Day7::NUMBER, index, line, bid)?;
 
-  Hand::new(&cards, bid, joker)
+  Ok(Round { cards, bid })
 }
 
-fn solve(input_file_path: &str, part: &str) -> u32 {
-  let mut hands = BufReader::new(fs::File::open(input_file_path).unwrap())
+/// One round per line, `<cards> <bid>`.
+pub fn parse_input(input: &str) -> Result<Vec<Round>> {
+  input
     .lines()
-    .map(|line| parse_input(&line.unwrap(), part == "b"))
+    .enumerate()
+    .map(|(index, line)| parse_round(index, line))
+    .collect()
+}
+
+fn solve(rounds: &[Round], joker: bool) -> u32 {
+  let mut hands = rounds
+    .iter()
+    .map(|round| {
+      let cards = round
+        .cards
+        .iter()
+        .map(|c| Card::parse(*c, joker).expect("cards are checked by parse_input"))
+        .collect::<Vec<Card>>();
+
+      Hand::new(&cards, round.bid, joker)
+    })
     .collect::<Vec<Hand>>();
 
   hands.sort();
@@ -344,25 +392,65 @@ fn solve(input_file_path: &str, part: &str) -> u32 {
     .sum()
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  solve(input_file_path, "a").try_into().unwrap()
+/// The total winnings, with `J` a jack.
+pub fn solve_a(rounds: &[Round]) -> Result<Answer> {
+  Ok(solve(rounds, false).into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  solve(input_file_path, "b").try_into().unwrap()
+/// The total winnings, with `J` a joker that counts as the card making the
+/// best hand but ranks lowest on its own.
+pub fn solve_b(rounds: &[Round]) -> Result<Answer> {
+  Ok(solve(rounds, true).into())
+}
+
+/// The puzzle, see `Day`.
+pub struct Day7;
+
+impl Day for Day7 {
+  type Input = Vec<Round>;
+
+  const NUMBER: u8 = 7;
+  const TITLE: &'static str = "Camel Cards";
+
+  fn parse(input: &str) -> Result<Vec<Round>> {
+    parse_input(input)
+  }
+
+  fn part_a(rounds: &Vec<Round>, _: &Params) -> Result<Answer> {
+    solve_a(rounds)
+  }
+
+  fn part_b(rounds: &Vec<Round>, _: &Params) -> Result<Answer> {
+    solve_b(rounds)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day7.txt"), 6440);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(6440.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day7.txt"), 5905);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(5905.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("32T3K 765\nT55X5 684") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 4)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
   }
 }
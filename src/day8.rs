@@ -1,47 +1,98 @@
+//! Day 8: Haunted Wasteland, following left/right turns through a network.
+
 use std::collections::HashMap;
-use std::fs;
 
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{Error, Result};
+use crate::math;
+use crate::params::Params;
+
+/// Every node with its left and right neighbour.
+pub type Network = HashMap<String, (String, String)>;
+
+/// One left/right instruction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
+  /// `L`, take the first neighbour.
   Left,
+  /// `R`, take the second neighbour.
   Right,
 }
 
-fn parse_input(input: &str) -> (Vec<Direction>, HashMap<String, (String, String)>) {
+/// The puzzle input: the left/right instructions and the network.
+#[derive(Debug)]
+pub struct Documents {
+  /// The instructions, repeated as often as needed.
+  pub directions: Vec<Direction>,
+  /// The nodes and where each one leads.
+  pub network: Network,
+}
+
+/// The instructions, a blank line, then `<node> = (<left>, <right>)` lines.
+pub fn parse_input(input: &str) -> Result<Documents> {
   let mut lines = input.lines();
-  let steps_unparsed = lines.next().unwrap();
+  let steps_unparsed = lines.next().unwrap_or_default();
   let steps = steps_unparsed
-    .chars()
-    .map(|step| match step {
-      'L' => Direction::Left,
-      'R' => Direction::Right,
-      c => panic!("Invalid step: {}", c),
+    .char_indices()
+    .map(|(i, step)| match step {
+      'L' => Ok(Direction::Left),
+      'R' => Ok(Direction::Right),
+      c => Err(Error::parse(
+        Day8::NUMBER,
+        0,
+        steps_unparsed,
+        &steps_unparsed[i..],
+        format!("invalid step `{}`", c),
+      )),
     })
-    .collect();
+    .collect::<Result<Vec<Direction>>>()?;
+
+  if steps.is_empty() {
+    return Err(Error::parse(Day8::NUMBER, 0, "", "", "missing steps"));
+  }
 
   let mut map = HashMap::new();
 
-  for line in lines.skip(1) {
-    let (name, children) = line.split_once(" = ").unwrap();
-    let (left, right) = children[1..children.len() - 1].split_once(", ").unwrap();
+  for (index, line) in lines.enumerate().skip(1) {
+    let error = |message: &str| Error::parse(Day8::NUMBER, index + 1, line, line, message);
+
+    let (name, children) = line
+      .split_once(" = ")
+      .ok_or_else(|| error("expected `<node> = (<left>, <right>)`"))?;
+    let (left, right) = children
+      .strip_prefix('(')
+      .and_then(|children| children.strip_suffix(')'))
+      .and_then(|children| children.split_once(", "))
+      .ok_or_else(|| error("expected `(<left>, <right>)`"))?;
 
     map.insert(name.to_string(), (left.to_string(), right.to_string()));
   }
 
-  (steps, map)
+  Ok(Documents {
+    directions: steps,
+    network: map,
+  })
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let input_unparsed = fs::read_to_string(input_file_path).unwrap();
-  let (directions, map) = parse_input(&input_unparsed);
-
+fn walk<'a>(
+  map: &'a Network,
+  directions: &[Direction],
+  start: &'a str,
+  is_end: impl Fn(&str) -> bool,
+) -> Result<usize> {
   let mut steps = 0;
-  let mut current_node = "AAA";
+  let mut current_node = start;
 
-  while current_node != "ZZZ" {
-    let (left, right) = map.get(current_node).unwrap();
+  while !is_end(current_node) {
+    let (left, right) = map.get(current_node).ok_or_else(|| {
+      Error::unsolvable(
+        Day8::NUMBER,
+        format!("node {} is not in the map", current_node),
+      )
+    })?;
     let next_node = if directions[steps % directions.len()] == Direction::Left {
       left
     } else {
@@ -52,36 +103,28 @@ pub fn solve_a(input_file_path: &str) -> u64 {
     steps += 1;
   }
 
-  steps.try_into().unwrap()
+  Ok(steps)
 }
 
-fn lcm(first: usize, second: usize) -> usize {
-  first * second / gcd(first, second)
-}
+/// Steps from `AAA` to `ZZZ`.
+pub fn solve_a(documents: &Documents) -> Result<Answer> {
+  let Documents {
+    directions,
+    network: map,
+  } = documents;
 
-fn gcd(first: usize, second: usize) -> usize {
-  let mut max = first;
-  let mut min = second;
-  if min > max {
-    let val = max;
-    max = min;
-    min = val;
-  }
+  let steps = walk(map, directions, "AAA", |node| node == "ZZZ")?;
 
-  loop {
-    let res = max % min;
-    if res == 0 {
-      return min;
-    }
-
-    max = min;
-    min = res;
-  }
+  Ok(steps.into())
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  let input_unparsed = fs::read_to_string(input_file_path).unwrap();
-  let (directions, map) = parse_input(&input_unparsed);
+/// Steps until every walk that starts on a node ending in `A` is on a node
+/// ending in `Z` at once.
+pub fn solve_b(documents: &Documents) -> Result<Answer> {
+  let Documents {
+    directions,
+    network: map,
+  } = documents;
 
   let starting_nodes: Vec<String> = map
     .iter()
@@ -89,50 +132,129 @@ pub fn solve_b(input_file_path: &str) -> u64 {
     .map(|(name, _)| name.to_string())
     .collect();
 
+  if starting_nodes.is_empty() {
+    return Err(Error::unsolvable(
+      Day8::NUMBER,
+      "no starting nodes ending in A",
+    ));
+  }
+
   let current_nodes = starting_nodes.clone();
 
   let steps = current_nodes
     .par_iter()
     .map(|current_node| {
-      let mut steps = 0;
-      let mut current_node = current_node.as_str();
       let map = map.clone();
       let directions = directions.clone();
 
-      while !current_node.ends_with("Z") {
-        let (left, right) = map.get(current_node).unwrap();
-        let next_node = if directions[steps % directions.len()] == Direction::Left {
-          left
-        } else {
-          right
-        };
-
-        current_node = next_node;
-        steps += 1;
-      }
-
-      steps as u32
+      walk(&map, &directions, current_node, |node| node.ends_with("Z"))
     })
-    .collect::<Vec<u32>>();
+    .collect::<Result<Vec<usize>>>()?;
 
   let res = steps
     .iter()
-    .fold(1, |acc, &step| lcm(acc as usize, step as usize));
+    .try_fold(1, |acc, &step| math::lcm(acc, step as u128))
+    .ok_or_else(|| Error::unsolvable(Day8::NUMBER, "the number of steps overflows"))?;
 
-  res.try_into().unwrap()
+  Ok(res.into())
+}
+
+/// The puzzle, see `Day`.
+pub struct Day8;
+
+impl Day for Day8 {
+  type Input = Documents;
+
+  const NUMBER: u8 = 8;
+  const TITLE: &'static str = "Haunted Wasteland";
+
+  fn parse(input: &str) -> Result<Documents> {
+    parse_input(input)
+  }
+
+  fn part_a(documents: &Documents, _: &Params) -> Result<Answer> {
+    solve_a(documents)
+  }
+
+  fn part_b(documents: &Documents, _: &Params) -> Result<Answer> {
+    solve_b(documents)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::input::normalize;
+
+  const EXAMPLE_A1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+
+  const EXAMPLE_A2: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+  const EXAMPLE_B: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
 
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day8a.txt"), 6);
+    assert_eq!(solve_a(&parse_input(EXAMPLE_A1).unwrap()), Ok(2.into()));
+    assert_eq!(solve_a(&parse_input(EXAMPLE_A2).unwrap()), Ok(6.into()));
+    // no AAA node to start from
+    assert!(matches!(
+      solve_a(&parse_input(EXAMPLE_B).unwrap()),
+      Err(Error::Unsolvable { .. })
+    ));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day8b.txt"), 6);
+    assert_eq!(solve_b(&parse_input(EXAMPLE_B).unwrap()), Ok(6.into()));
+    // AAA is the only start, so part b walks the same path as part a
+    assert_eq!(solve_b(&parse_input(EXAMPLE_A1).unwrap()), Ok(2.into()));
+    assert_eq!(solve_b(&parse_input(EXAMPLE_A2).unwrap()), Ok(6.into()));
+  }
+
+  #[test]
+  fn test_parse_error() {
+    match parse_input("LXR\n\nAAA = (ZZZ, ZZZ)") {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (1, 2)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+
+    match parse_input("LR\n\nAAA = (ZZZ, ZZZ\nZZZ = (ZZZ, ZZZ)") {
+      Err(Error::Parse { line, .. }) => assert_eq!(line, 3),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_crlf_input() {
+    let crlf = format!("\u{feff}{}\r\n\r\n", EXAMPLE_A2.replace('\n', "\r\n"));
+
+    assert_eq!(
+      solve_a(&parse_input(&normalize(&crlf)).unwrap()),
+      Ok(6.into())
+    );
   }
 }
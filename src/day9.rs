@@ -1,64 +1,128 @@
-use std::fs;
+//! Day 9: Mirage Maintenance, extrapolating histories from their differences.
 
-fn solve(input: &str, reverse: bool) -> u64 {
+use crate::answer::Answer;
+use crate::day::Day;
+use crate::error::{parse_number, Error, Result};
+use crate::params::Params;
+
+/// One history of readings per line.
+pub fn parse_input(input: &str) -> Result<Vec<Vec<i64>>> {
   input
     .lines()
-    .map(|line| {
-      let mut histories: Vec<Vec<i64>> = Vec::new();
+    .enumerate()
+    .map(|(index, line)| {
+      let history = line
+        .split_whitespace()
+        .map(|x| parse_number::<i64>(Day9::NUMBER, index, line, x))
+        .collect::<Result<Vec<i64>>>()?;
 
-      let first_history: Vec<i64> = line
-        .split(" ")
-        .filter_map(|x| x.parse::<i64>().ok())
-        .collect();
+      if history.is_empty() {
+        return Err(Error::parse(
+          Day9::NUMBER,
+          index,
+          line,
+          line,
+          "empty history",
+        ));
+      }
 
-      histories.push(if reverse {
-        first_history.iter().rev().map(|x| *x).collect()
-      } else {
-        first_history
-      });
+      Ok(history)
+    })
+    .collect()
+}
 
-      while !histories.last().unwrap().iter().all(|x| *x == 0) {
-        let last = histories.last().unwrap();
-        let mut next_history: Vec<i64> = Vec::new();
+fn solve(readings: &[Vec<i64>], reverse: bool) -> Result<Answer> {
+  let sum = readings
+    .iter()
+    .map(|first_history| {
+      let mut history: Vec<i64> = if reverse {
+        first_history.iter().rev().copied().collect()
+      } else {
+        first_history.clone()
+      };
+      let mut next = 0;
 
-        for i in 0..last.len() - 1 {
-          next_history.push(last[i + 1] - last[i]);
-        }
+      // `all` holds for an empty row too, which only follows a single non-zero
+      while history.is_empty() || !history.iter().all(|x| *x == 0) {
+        let Some(last) = history.last() else {
+          return Err(Error::unsolvable(
+            Day9::NUMBER,
+            "history never reaches all zeros",
+          ));
+        };
+        next += last;
 
-        histories.push(next_history);
+        history = history.windows(2).map(|pair| pair[1] - pair[0]).collect();
       }
 
-      histories
-        .iter()
-        .map(|history| history.last().unwrap())
-        .sum::<i64>()
+      Ok(next)
     })
-    .sum::<i64>() as u64
+    .sum::<Result<i64>>()?;
+
+  Ok(sum.into())
 }
 
-pub fn solve_a(input_file_path: &str) -> u64 {
-  let input = fs::read_to_string(input_file_path).unwrap();
+/// Sums the next value of every history.
+pub fn solve_a(readings: &[Vec<i64>]) -> Result<Answer> {
+  solve(readings, false)
+}
 
-  solve(&input, false)
+/// Sums the value before the first of every history.
+pub fn solve_b(readings: &[Vec<i64>]) -> Result<Answer> {
+  solve(readings, true)
 }
 
-pub fn solve_b(input_file_path: &str) -> u64 {
-  let input = fs::read_to_string(input_file_path).unwrap();
+/// The puzzle, see `Day`.
+pub struct Day9;
+
+impl Day for Day9 {
+  type Input = Vec<Vec<i64>>;
+
+  const NUMBER: u8 = 9;
+  const TITLE: &'static str = "Mirage Maintenance";
+
+  fn parse(input: &str) -> Result<Vec<Vec<i64>>> {
+    parse_input(input)
+  }
+
+  fn part_a(readings: &Vec<Vec<i64>>, _: &Params) -> Result<Answer> {
+    solve_a(readings)
+  }
 
-  solve(&input, true)
+  fn part_b(readings: &Vec<Vec<i64>>, _: &Params) -> Result<Answer> {
+    solve_b(readings)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
   #[test]
   fn test_solve_a() {
-    assert_eq!(solve_a(".\\src\\test_input\\day9.txt"), 114);
+    assert_eq!(solve_a(&parse_input(EXAMPLE).unwrap()), Ok(114.into()));
   }
 
   #[test]
   fn test_solve_b() {
-    assert_eq!(solve_b(".\\src\\test_input\\day9.txt"), 2);
+    assert_eq!(solve_b(&parse_input(EXAMPLE).unwrap()), Ok(2.into()));
+  }
+
+  #[test]
+  fn test_solve_negative() {
+    assert_eq!(solve_a(&parse_input("-1 -4 -7").unwrap()), Ok((-10).into()));
+  }
+
+  #[test]
+  fn test_solve_no_zeros() {
+    assert!(matches!(
+      solve_a(&parse_input("1 2 4").unwrap()),
+      Err(Error::Unsolvable { .. })
+    ));
   }
 }
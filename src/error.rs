@@ -0,0 +1,212 @@
+//! The errors parsing and solving can end in.
+
+use std::any::Any;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Anything that keeps a part from getting its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+  /// Malformed puzzle input, `line` and `column` are 1-based.
+  Parse {
+    /// The day whose input it is.
+    day: u8,
+    /// The line the problem is on.
+    line: usize,
+    /// Where on `line` it is, in chars.
+    column: usize,
+    /// The whole line, to quote it.
+    text: String,
+    /// What is wrong.
+    message: String,
+  },
+  /// Input that parses fine, but has no answer, e.g. a missing start node.
+  Unsolvable {
+    /// The day that has no answer.
+    day: u8,
+    /// Why.
+    message: String,
+  },
+  /// A `--param` value the day could not use.
+  Param {
+    /// The day that declares the parameter.
+    day: u8,
+    /// The parameter.
+    name: String,
+    /// What is wrong with its value.
+    message: String,
+  },
+  /// The input could not be read at all.
+  Io {
+    /// The file that could not be read.
+    path: PathBuf,
+    /// The error reading it gave.
+    message: String,
+  },
+  /// A solver panicked, the run carries on with the other days.
+  Panic {
+    /// The day that panicked.
+    day: u8,
+    /// What it panicked with.
+    message: String,
+  },
+  /// A solver ran past `--timeout`.
+  Timeout {
+    /// The day that ran too long.
+    day: u8,
+    /// The `--timeout` it ran past.
+    limit: Duration,
+  },
+}
+
+/// `Result` with this crate's `Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+  /// Builds a parse error for line `line_index` (0-based, as from
+  /// `lines().enumerate()`), pointing at `token`. `token` should be a slice of
+  /// `text` so the column can be worked out, anything else points at column 1.
+  pub fn parse(
+    day: u8,
+    line_index: usize,
+    text: &str,
+    token: &str,
+    message: impl Into<String>,
+  ) -> Error {
+    let start = text.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+    let column = match offset <= text.len() && text.is_char_boundary(offset) {
+      true => text[..offset].chars().count() + 1,
+      false => 1,
+    };
+
+    Error::Parse {
+      day,
+      line: line_index + 1,
+      column,
+      text: text.to_string(),
+      message: message.into(),
+    }
+  }
+
+  /// An `Error::Unsolvable` of `day`.
+  pub fn unsolvable(day: u8, message: impl Into<String>) -> Error {
+    Error::Unsolvable {
+      day,
+      message: message.into(),
+    }
+  }
+
+  /// A panic caught with `catch_unwind`, carrying the message it was raised
+  /// with.
+  pub fn panic(day: u8, payload: &(dyn Any + Send)) -> Error {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+      message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+      message.clone()
+    } else {
+      "unknown panic".to_string()
+    };
+
+    Error::Panic { day, message }
+  }
+}
+
+/// Parses `token`, a slice of line `text`, reporting a parse error pointing at
+/// it when it is not a valid number.
+pub fn parse_number<T: FromStr>(day: u8, line_index: usize, text: &str, token: &str) -> Result<T> {
+  token.parse::<T>().map_err(|_| {
+    Error::parse(
+      day,
+      line_index,
+      text,
+      token,
+      format!("invalid number `{}`", token),
+    )
+  })
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Parse {
+        day,
+        line,
+        column,
+        text,
+        message,
+      } => {
+        let gutter = " ".repeat(line.to_string().len());
+
+        writeln!(
+          f,
+          "day {}: {} at line {}, column {}",
+          day, message, line, column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line, text)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(column - 1))
+      }
+      Error::Unsolvable { day, message } => write!(f, "day {}: {}", day, message),
+      Error::Param { day, name, message } => {
+        write!(f, "day {}: --param {}: {}", day, name, message)
+      }
+      Error::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+      Error::Panic { day, message } => write!(f, "day {}: panicked: {}", day, message),
+      Error::Timeout { day, limit } => {
+        write!(f, "day {}: timed out after {}s", day, limit.as_secs_f64())
+      }
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_column() {
+    let text = "32T3X 765";
+
+    match Error::parse(7, 2, text, &text[4..5], "invalid card") {
+      Error::Parse { line, column, .. } => assert_eq!((line, column), (3, 5)),
+      other => panic!("unexpected error: {:?}", other),
+    }
+
+    match Error::parse(7, 0, text, "elsewhere", "invalid card") {
+      Error::Parse { column, .. } => assert_eq!(column, 1),
+      other => panic!("unexpected error: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_parse_number() {
+    let text = "Card 1: 41 4x | 83";
+
+    assert_eq!(parse_number::<u32>(4, 0, text, &text[8..10]), Ok(41));
+    assert_eq!(
+      parse_number::<u32>(4, 0, text, &text[11..13]),
+      Err(Error::Parse {
+        day: 4,
+        line: 1,
+        column: 12,
+        text: text.to_string(),
+        message: "invalid number `4x`".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn test_display() {
+    let text = "Game 1: 3 blue, x red";
+
+    assert_eq!(
+      Error::parse(2, 0, text, &text[16..17], "invalid amount").to_string(),
+      "day 2: invalid amount at line 1, column 17\n  |\n1 | Game 1: 3 blue, x red\n  |                 ^"
+    );
+  }
+}
@@ -0,0 +1,265 @@
+//! A rectangular grid of cells, as many puzzles come as a map of chars.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+
+/// A `(row, column)` position in a `Grid`.
+pub type Coord = (usize, usize);
+
+/// Row and column offsets of the 4 orthogonal neighbours.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Row and column offsets of all 8 neighbours, diagonals included.
+pub const ADJACENT: [(isize, isize); 8] = [
+  (-1, -1),
+  (-1, 0),
+  (-1, 1),
+  (0, 1),
+  (1, 1),
+  (1, 0),
+  (1, -1),
+  (0, -1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+  /// Parses one cell per char of every line of `input`. Lines of a different
+  /// width than the first one, and chars `cell` turns down, are parse errors
+  /// of `day`.
+  pub fn parse(day: u8, input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>> {
+    let width = input.lines().next().unwrap_or_default().chars().count();
+    let mut cells = vec![];
+    let mut height = 0;
+
+    for (y, line) in input.lines().enumerate() {
+      for (i, symbol) in line.char_indices() {
+        cells.push(cell(symbol).ok_or_else(|| {
+          Error::parse(
+            day,
+            y,
+            line,
+            &line[i..],
+            format!("unknown symbol `{}`", symbol),
+          )
+        })?);
+      }
+
+      let found = line.chars().count();
+      if found != width {
+        return Err(Error::parse(
+          day,
+          y,
+          line,
+          &line[line.len()..],
+          format!("expected {} columns, found {}", width, found),
+        ));
+      }
+      height += 1;
+    }
+
+    Ok(Grid {
+      width,
+      height,
+      cells,
+    })
+  }
+
+  /// The number of columns.
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  /// The number of rows.
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  /// The cell at `coord`, `None` outside the grid.
+  pub fn get(&self, (y, x): Coord) -> Option<&T> {
+    (y < self.height && x < self.width).then(|| &self.cells[y * self.width + x])
+  }
+
+  /// The cell at `coord`, `None` outside the grid.
+  pub fn get_mut(&mut self, (y, x): Coord) -> Option<&mut T> {
+    (y < self.height && x < self.width).then(|| &mut self.cells[y * self.width + x])
+  }
+
+  /// `coord` moved by `offset`, `None` when that leaves the grid.
+  pub fn step(&self, (y, x): Coord, (dy, dx): (isize, isize)) -> Option<Coord> {
+    let y = y.checked_add_signed(dy).filter(|y| *y < self.height)?;
+    let x = x.checked_add_signed(dx).filter(|x| *x < self.width)?;
+
+    Some((y, x))
+  }
+
+  /// The orthogonal neighbours of `coord` inside the grid.
+  pub fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+    ORTHOGONAL
+      .iter()
+      .filter_map(move |offset| self.step(coord, *offset))
+  }
+
+  /// All neighbours of `coord` inside the grid, diagonals included.
+  pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+    ADJACENT
+      .iter()
+      .filter_map(move |offset| self.step(coord, *offset))
+  }
+
+  /// Every row, top to bottom.
+  pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+    // `max(1)` as `chunks` rejects 0, a grid of empty lines has no cells anyway
+    self.cells.chunks(self.width.max(1))
+  }
+
+  /// Row `y`, `None` below the grid.
+  pub fn row(&self, y: usize) -> Option<&[T]> {
+    (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+  }
+
+  /// The cells of column `x` from top to bottom, `None` right of the grid.
+  pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+    (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+  }
+
+  /// Every column, left to right, each from top to bottom.
+  pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+    (0..self.width).map(|x| self.cells.iter().skip(x).step_by(self.width))
+  }
+
+  /// Every cell with its coordinate, row by row.
+  pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+    self
+      .cells
+      .iter()
+      .enumerate()
+      .map(|(i, cell)| ((i / self.width, i % self.width), cell))
+  }
+
+  /// The coordinate of the first cell, row by row, that matches `predicate`.
+  pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Coord> {
+    self
+      .iter()
+      .find(|(_, cell)| predicate(cell))
+      .map(|(coord, _)| coord)
+  }
+
+  /// Rows become columns and columns rows.
+  pub fn transpose(&self) -> Grid<T>
+  where
+    T: Clone,
+  {
+    Grid {
+      width: self.height,
+      height: self.width,
+      cells: self.columns().flatten().cloned().collect(),
+    }
+  }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, coord: Coord) -> &T {
+    self
+      .get(coord)
+      .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+  }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+  fn index_mut(&mut self, coord: Coord) -> &mut T {
+    self
+      .get_mut(coord)
+      .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+  }
+}
+
+/// One line per row, without a trailing line break.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (y, row) in self.rows().enumerate() {
+      if y > 0 {
+        writeln!(f)?;
+      }
+      for cell in row {
+        write!(f, "{}", cell)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn grid(input: &str) -> Grid<char> {
+    Grid::parse(0, input, Some).unwrap()
+  }
+
+  #[test]
+  fn test_parse() {
+    let grid = grid("ab.\n.cd");
+
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(1, 2)], 'd');
+    assert_eq!(grid.get((2, 0)), None);
+    assert_eq!(grid.get((0, 3)), None);
+    assert_eq!(grid.position(|c| *c == 'c'), Some((1, 1)));
+    assert_eq!(grid.to_string(), "ab.\n.cd");
+
+    match Grid::parse(0, "ab\nabc", Some) {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 4)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+    match Grid::parse(0, "ab\nxb", |c| (c != 'x').then_some(c)) {
+      Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 1)),
+      other => panic!("expected a parse error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_neighbours() {
+    let grid = grid("abc\ndef\nghi");
+
+    assert_eq!(
+      grid.neighbours4((0, 0)).collect::<Vec<Coord>>(),
+      vec![(0, 1), (1, 0)]
+    );
+    assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+    assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    assert_eq!(
+      grid.neighbours8((2, 2)).collect::<Vec<Coord>>(),
+      vec![(1, 1), (1, 2), (2, 1)]
+    );
+    assert_eq!(grid.step((0, 0), (-1, 0)), None);
+  }
+
+  #[test]
+  fn test_rows_columns_transpose() {
+    let grid = grid("abc\ndef");
+
+    assert_eq!(
+      grid.rows().collect::<Vec<&[char]>>(),
+      vec![['a', 'b', 'c'], ['d', 'e', 'f']]
+    );
+    assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+    assert_eq!(grid.row(2), None);
+    assert_eq!(grid.column(1).unwrap().collect::<String>(), "be");
+    assert!(grid.column(3).is_none());
+    assert_eq!(grid.columns().count(), 3);
+    assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+    assert_eq!(grid.transpose().transpose(), grid);
+  }
+}
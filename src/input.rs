@@ -0,0 +1,157 @@
+//! Reading puzzle inputs and cleaning them up before they are parsed.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// Overrides the directory the `dayN.txt` inputs are looked up in.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Where the input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+  /// `dayN.txt` in the input directory.
+  Default,
+  /// An explicit `--input <file>`.
+  File(PathBuf),
+  /// `--input -`
+  Stdin,
+}
+
+impl Source {
+  /// The source an `--input` argument names, `-` being stdin.
+  pub fn from_arg(arg: &str) -> Source {
+    match arg {
+      "-" => Source::Stdin,
+      path => Source::File(PathBuf::from(path)),
+    }
+  }
+
+  /// Reads the puzzle input for `day`, this is the only place the solvers'
+  /// input touches the file system. The text is `normalize`d.
+  pub fn read(&self, day: u8) -> Result<String> {
+    let path = match self {
+      Source::Default => day_path(day),
+      Source::File(path) => path.clone(),
+      Source::Stdin => {
+        let mut content = String::new();
+        io::stdin()
+          .read_to_string(&mut content)
+          .map_err(|err| Error::Io {
+            path: PathBuf::from("-"),
+            message: err.to_string(),
+          })?;
+        return Ok(normalize(&content));
+      }
+    };
+
+    if !path.is_file() {
+      return Err(Error::Io {
+        path,
+        message: "input file not found".to_string(),
+      });
+    }
+
+    fs::read_to_string(&path)
+      .map(|content| normalize(&content))
+      .map_err(|err| Error::Io {
+        path,
+        message: err.to_string(),
+      })
+  }
+}
+
+/// Makes CRLF and LF files look the same to the day parsers: strips a UTF-8
+/// BOM, turns CRLF into LF and drops trailing whitespace, both at the end of
+/// each line and trailing blank lines.
+pub fn normalize(input: &str) -> String {
+  let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+  input
+    .lines()
+    .map(str::trim_end)
+    .collect::<Vec<&str>>()
+    .join("\n")
+    .trim_end()
+    .to_string()
+}
+
+/// The directory of the `dayN.txt` inputs, `src/input` unless
+/// `AOC_INPUT_DIR` says otherwise.
+pub fn input_dir() -> PathBuf {
+  match env::var_os(INPUT_DIR_VAR) {
+    Some(dir) => PathBuf::from(dir),
+    None => Path::new("src").join("input"),
+  }
+}
+
+/// The default input file of `day`.
+pub fn day_path(day: u8) -> PathBuf {
+  input_dir().join(format!("day{}.txt", day))
+}
+
+/// The example a day's tests run on, as written by `aoc new`.
+pub fn example_path(day: u8) -> PathBuf {
+  Path::new("src")
+    .join("examples")
+    .join(format!("day{}.txt", day))
+}
+
+/// SHA-256 of the input as lowercase hex, identifies which input an answer or
+/// timing belongs to.
+pub fn checksum(input: &str) -> String {
+  Sha256::digest(input.as_bytes())
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_arg() {
+    assert_eq!(Source::from_arg("-"), Source::Stdin);
+    assert_eq!(
+      Source::from_arg("day1.txt"),
+      Source::File(PathBuf::from("day1.txt"))
+    );
+  }
+
+  #[test]
+  fn test_read_missing_file() {
+    let err = Source::File(PathBuf::from("missing").join("day1.txt"))
+      .read(1)
+      .unwrap_err();
+
+    assert!(err
+      .to_string()
+      .contains(&Path::new("missing").join("day1.txt").display().to_string()));
+  }
+
+  #[test]
+  fn test_checksum() {
+    assert_eq!(
+      checksum("abc"),
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+  }
+
+  #[test]
+  fn test_normalize() {
+    let lf = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2";
+
+    assert_eq!(normalize(lf), lf);
+    assert_eq!(normalize(&format!("{}\n\n\n", lf)), lf);
+    assert_eq!(
+      normalize("\u{feff}seeds: 79 14  \r\n\r\nseed-to-soil map:\r\n50 98 2\r\n\r\n"),
+      lf
+    );
+  }
+}
@@ -0,0 +1,84 @@
+#![warn(missing_docs)]
+//! Advent of Code 2023 solutions.
+//!
+//! Every `dayN` module has a unit struct implementing [`Day`], a
+//! `parse_input` function and `solve_a`/`solve_b` functions that take what it
+//! parsed. [`registry`]
+//! lists all of them behind the object safe [`Puzzle`] trait, which is what
+//! the `aoc-rust-2023` binary runs.
+//!
+//! ```
+//! use aoc_rust_2023::{day6, registry, Part, Params};
+//!
+//! let input = "Time: 7 15 30\nDistance: 9 40 200";
+//! let races = day6::parse_input(input).unwrap();
+//! assert_eq!(day6::solve_a(&races), Ok(288.into()));
+//!
+//! let day = registry()[5];
+//! let params = Params::new(day.number(), day.params(), &[]);
+//! assert_eq!(day.solve(Part::B, input, &params), Ok(71503.into()));
+//! ```
+
+pub mod answer;
+pub mod day;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod math;
+pub mod params;
+#[cfg(test)]
+mod test_util;
+
+// the command line of the binary, public only so `main.rs` can reach it
+#[doc(hidden)]
+pub mod alloc;
+#[doc(hidden)]
+pub mod answers;
+#[doc(hidden)]
+pub mod app;
+#[doc(hidden)]
+pub mod bench;
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod perf;
+#[doc(hidden)]
+pub mod report;
+#[doc(hidden)]
+pub mod runner;
+#[doc(hidden)]
+pub mod scaffold;
+#[doc(hidden)]
+pub mod watch;
+
+pub use answer::Answer;
+pub use day::{Day, Part, Puzzle};
+pub use error::{Error, Result};
+pub use params::{Param, Params};
+
+/// Declares the day modules and builds the registry from the same list, so a
+/// new day only has to be added here once. `aoc new <day>` adds it for you.
+macro_rules! days {
+  ($($module:ident :: $day:ident),* $(,)?) => {
+    $(pub mod $module;)*
+
+    /// Every solved day, in calendar order.
+    pub fn registry() -> Vec<&'static dyn Puzzle> {
+      vec![$(&$module::$day),*]
+    }
+  };
+}
+
+days! {
+  day1::Day1,
+  day2::Day2,
+  day3::Day3,
+  day4::Day4,
+  day5::Day5,
+  day6::Day6,
+  day7::Day7,
+  day8::Day8,
+  day9::Day9,
+  day10::Day10,
+  day11::Day11,
+}
@@ -0,0 +1,156 @@
+//! Number theory a few puzzles need.
+
+/// Greatest common divisor, `gcd(0, n)` is `n`.
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+  while b != 0 {
+    (a, b) = (b, a % b);
+  }
+
+  a
+}
+
+/// Least common multiple, `None` when it does not fit in a `u128`.
+/// `lcm(0, n)` is 0.
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+  if a == 0 || b == 0 {
+    return Some(0);
+  }
+
+  (a / gcd(a, b)).checked_mul(b)
+}
+
+/// `(g, x, y)` with `a * x + b * y == g`, where `g` is the non-negative
+/// greatest common divisor of `a` and `b`. Either being `i128::MIN` can
+/// overflow and panic, `extended_gcd(i128::MIN, 0)` for one would be 2^127.
+/// `mod_inverse` and `crt` only pass it non-negative numbers.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+  let (mut old_r, mut r) = (a, b);
+  let (mut old_x, mut x) = (1, 0);
+  let (mut old_y, mut y) = (0, 1);
+
+  while r != 0 {
+    let quotient = old_r / r;
+    (old_r, r) = (r, old_r - quotient * r);
+    (old_x, x) = (x, old_x - quotient * x);
+    (old_y, y) = (y, old_y - quotient * y);
+  }
+
+  match old_r < 0 {
+    true => (-old_r, -old_x, -old_y),
+    false => (old_r, old_x, old_y),
+  }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, `None` when `a`
+/// and `modulus` share a factor.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+  if modulus <= 0 {
+    return None;
+  }
+
+  let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+  (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every `(residue, modulus)` pair,
+/// the moduli need not be coprime. Returns the smallest non-negative `x` and
+/// the lcm of the moduli, every solution is `x` plus a multiple of it. `None`
+/// when the congruences contradict each other, a modulus is not positive or
+/// the lcm overflows.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+  congruences
+    .iter()
+    .try_fold((0, 1), |(x, modulus), &(residue, other)| {
+      if other <= 0 {
+        return None;
+      }
+
+      // both in `0..`, so their difference cannot overflow
+      let residue = residue.rem_euclid(other);
+      let (g, _, _) = extended_gcd(modulus, other);
+      let difference = residue - x;
+      if difference.rem_euclid(g) != 0 {
+        return None;
+      }
+
+      // x + modulus * k ≡ residue (mod other), solved for k modulo other / g
+      let step = other / g;
+      let inverse = mod_inverse(modulus / g, step)?;
+      let k = (difference / g).rem_euclid(step).checked_mul(inverse)? % step;
+
+      let combined = (modulus / g).checked_mul(other)?;
+      let x = modulus.checked_mul(k)?.checked_add(x)?.rem_euclid(combined);
+
+      Some((x, combined))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_gcd_lcm() {
+    assert_eq!(gcd(12, 18), 6);
+    assert_eq!(gcd(0, 7), 7);
+    assert_eq!(gcd(7, 0), 7);
+    assert_eq!(gcd(0, 0), 0);
+
+    assert_eq!(lcm(4, 6), Some(12));
+    assert_eq!(lcm(0, 6), Some(0));
+    assert_eq!(lcm(1 << 100, 3), Some(3 << 100));
+    assert_eq!(lcm(u128::MAX, u128::MAX - 1), None);
+    // overflows as `a * b` first, but not as `a / gcd * b`
+    assert_eq!(lcm(1 << 100, 1 << 120), Some(1 << 120));
+  }
+
+  #[test]
+  fn test_inverse() {
+    for (a, b) in [(240, 46), (-240, 46), (7, 0), (0, -7), (17, 5)] {
+      let (g, x, y) = extended_gcd(a, b);
+      assert_eq!(a * x + b * y, g);
+      assert_eq!(g as u128, gcd(a.unsigned_abs(), b.unsigned_abs()));
+    }
+
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(-3, 11), Some(7));
+    assert_eq!(mod_inverse(2, 4), None);
+    assert_eq!(mod_inverse(1, 0), None);
+  }
+
+  #[test]
+  fn test_crt() {
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+    assert_eq!(crt(&[]), Some((0, 1)));
+    assert_eq!(crt(&[(1, 0)]), None);
+    assert_eq!(crt(&[(i128::MAX - 1, i128::MAX), (i128::MIN, 2)]), None);
+    assert_eq!(crt(&[(i128::MIN, 3)]), Some((1, 3)));
+  }
+
+  #[test]
+  fn test_crt_brute_force() {
+    for m1 in 1..=8 {
+      for m2 in 1..=8 {
+        for r1 in 0..m1 {
+          for r2 in 0..m2 {
+            let modulus = lcm(m1 as u128, m2 as u128).unwrap() as i128;
+            let expected = (0..modulus).find(|x| x % m1 == r1 && x % m2 == r2);
+
+            assert_eq!(
+              crt(&[(r1, m1), (r2, m2)]),
+              expected.map(|x| (x, modulus)),
+              "x ≡ {} (mod {}), x ≡ {} (mod {})",
+              r1,
+              m1,
+              r2,
+              m2
+            );
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,141 @@
+//! Named solver parameters, settable with `--param name=value`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A named solver parameter, declared by a day in `Day::PARAMS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+  /// What `--param <name>=<value>` calls it.
+  pub name: &'static str,
+  /// The value the puzzle itself uses.
+  pub default: &'static str,
+  /// One line for the usage text.
+  pub description: &'static str,
+}
+
+impl fmt::Display for Param {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}={}", self.name, self.default)
+  }
+}
+
+/// The parameter values one day is solved with: its declared defaults, with
+/// any `--param name=value` overrides applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+  day: u8,
+  values: Vec<(&'static str, String)>,
+  overridden: bool,
+}
+
+impl Params {
+  /// Later overrides win, overrides for parameters `declared` does not list
+  /// are ignored, they may belong to another day.
+  pub fn new(day: u8, declared: &[Param], overrides: &[(String, String)]) -> Params {
+    let values: Vec<(&'static str, String)> = declared
+      .iter()
+      .map(|param| {
+        let value = overrides
+          .iter()
+          .rev()
+          .find(|(name, _)| name == param.name)
+          .map_or(param.default, |(_, value)| value.as_str());
+
+        (param.name, value.to_string())
+      })
+      .collect();
+
+    let overridden = values
+      .iter()
+      .zip(declared)
+      .any(|((_, value), param)| value != param.default);
+
+    Params {
+      day,
+      values,
+      overridden,
+    }
+  }
+
+  /// Whether any value differs from its default, the known answers only hold
+  /// for the defaults.
+  pub fn overridden(&self) -> bool {
+    self.overridden
+  }
+
+  /// The value of `name`, parsed as a `T`.
+  pub fn get<T: FromStr>(&self, name: &str) -> Result<T> {
+    let error = |message: String| Error::Param {
+      day: self.day,
+      name: name.to_string(),
+      message,
+    };
+
+    let value = self
+      .values
+      .iter()
+      .find(|(declared, _)| *declared == name)
+      .map(|(_, value)| value)
+      .ok_or_else(|| error("unknown parameter".to_string()))?;
+
+    value
+      .parse::<T>()
+      .map_err(|_| error(format!("invalid value `{}`", value)))
+  }
+}
+
+/// Splits a `--param` argument into its name and value.
+pub fn parse_override(arg: &str) -> std::result::Result<(String, String), String> {
+  match arg.split_once('=') {
+    Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+    _ => Err(format!("Expected --param <name=value>, got {}", arg)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PARAMS: &[Param] = &[
+    Param {
+      name: "expansion",
+      default: "1000000",
+      description: "",
+    },
+    Param {
+      name: "symbols",
+      default: "*#",
+      description: "",
+    },
+  ];
+
+  #[test]
+  fn test_get() {
+    let overrides = vec![
+      parse_override("expansion=10").unwrap(),
+      parse_override("red=12").unwrap(),
+      parse_override("expansion=100").unwrap(),
+    ];
+    let params = Params::new(11, PARAMS, &overrides);
+
+    assert_eq!(params.get::<u128>("expansion"), Ok(100));
+    assert!(params.overridden());
+    assert!(!Params::new(11, PARAMS, &overrides[1..2]).overridden());
+    assert_eq!(params.get::<String>("symbols"), Ok("*#".to_string()));
+    assert!(params.get::<u32>("red").is_err());
+    assert!(params.get::<u32>("symbols").is_err());
+  }
+
+  #[test]
+  fn test_parse_override() {
+    assert_eq!(
+      parse_override("red=12"),
+      Ok(("red".to_string(), "12".to_string()))
+    );
+    assert!(parse_override("red").is_err());
+    assert!(parse_override("=12").is_err());
+  }
+}
@@ -0,0 +1,341 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::bench::Bench;
+use crate::error::{Error, Result};
+use crate::runner::format_duration;
+
+/// Every `--bench` run appends one JSON line per day and part here.
+pub const HISTORY_FILE: &str = "perf_history.jsonl";
+
+/// How much slower, in percent, a phase may get before `perf compare` fails.
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+/// The median times of one benchmarked part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+  pub commit: String,
+  /// `input::checksum` of the input, timings on other inputs do not compare.
+  pub checksum: String,
+  pub day: u8,
+  pub part: String,
+  pub iterations: usize,
+  pub parse_ns: u64,
+  pub solve_ns: u64,
+}
+
+/// The commit the working tree is on, with `-dirty` when it has changes, so
+/// uncommitted experiments do not pass for the commit itself.
+pub fn git_commit() -> String {
+  let git = |args: &[&str]| {
+    Command::new("git")
+      .args(args)
+      .output()
+      .ok()
+      .filter(|output| output.status.success())
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+  };
+
+  match (
+    git(&["rev-parse", "--short", "HEAD"]),
+    git(&["status", "--porcelain", "--untracked-files=no"]),
+  ) {
+    (Some(commit), Some(changes)) if changes.is_empty() => commit,
+    (Some(commit), _) => format!("{}-dirty", commit),
+    (None, _) => "unknown".to_string(),
+  }
+}
+
+/// The history entries of `benches`, leaving out the ones that ran with
+/// overridden parameters, as their timings do not compare with the defaults.
+pub fn entries(commit: &str, benches: &[Bench]) -> Vec<Entry> {
+  let nanos = |duration: Duration| duration.as_nanos().try_into().unwrap_or(u64::MAX);
+
+  benches
+    .iter()
+    .filter(|bench| !bench.overridden)
+    .map(|bench| Entry {
+      commit: commit.to_string(),
+      checksum: bench.checksum.clone(),
+      day: bench.day,
+      part: bench.part.to_string(),
+      iterations: bench.iterations,
+      parse_ns: nanos(bench.parse.median),
+      solve_ns: nanos(bench.solve.median),
+    })
+    .collect()
+}
+
+/// Adds `entries` to the history at `path`, without creating the file when
+/// there is nothing to add.
+pub fn append(path: &Path, entries: &[Entry]) -> Result<()> {
+  if entries.is_empty() {
+    return Ok(());
+  }
+
+  let io_error = |err: std::io::Error| Error::Io {
+    path: path.to_path_buf(),
+    message: err.to_string(),
+  };
+
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .map_err(io_error)?;
+
+  for entry in entries {
+    let line = serde_json::to_string(entry).expect("entries always serialize");
+    writeln!(file, "{}", line).map_err(io_error)?;
+  }
+
+  Ok(())
+}
+
+/// The history in the order it was recorded, empty when nothing was
+/// benchmarked yet.
+pub fn load(path: &Path) -> Result<Vec<Entry>> {
+  if !path.exists() {
+    return Ok(vec![]);
+  }
+
+  let content = fs::read_to_string(path).map_err(|err| Error::Io {
+    path: path.to_path_buf(),
+    message: err.to_string(),
+  })?;
+
+  content
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(index, line)| {
+      serde_json::from_str(line).map_err(|err| Error::Io {
+        path: path.to_path_buf(),
+        message: format!("line {}: {}", index + 1, err),
+      })
+    })
+    .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+  pub day: u8,
+  pub part: String,
+  pub phase: &'static str,
+  pub baseline: Duration,
+  pub current: Duration,
+}
+
+impl Comparison {
+  /// How much slower `current` is, in percent, negative when it got faster.
+  pub fn change(&self) -> f64 {
+    let baseline = self.baseline.as_secs_f64();
+    if baseline == 0.0 {
+      return 0.0;
+    }
+
+    (self.current.as_secs_f64() - baseline) / baseline * 100.0
+  }
+}
+
+/// Compares the latest commit in `history` with `baseline`, a commit prefix,
+/// or by default the commit benchmarked before it. Only parts benchmarked on
+/// the same input at both commits are compared, using the latest entry of
+/// each. Returns both commits and the comparisons.
+pub fn compare(
+  history: &[Entry],
+  baseline: Option<&str>,
+) -> std::result::Result<(String, String, Vec<Comparison>), String> {
+  let current = history
+    .last()
+    .map(|entry| entry.commit.clone())
+    .ok_or("Nothing benchmarked yet, run with --bench first".to_string())?;
+
+  let baseline = history
+    .iter()
+    .rev()
+    .map(|entry| &entry.commit)
+    .find(|commit| match baseline {
+      Some(prefix) => commit.starts_with(prefix),
+      None => **commit != current,
+    })
+    .cloned()
+    .ok_or(match baseline {
+      Some(prefix) => format!("No benchmarks for commit {}", prefix),
+      None => format!(
+        "Only {} was benchmarked, nothing to compare it with",
+        current
+      ),
+    })?;
+
+  let latest = |commit: &str, day: u8, part: &str, checksum: &str| {
+    history.iter().rev().find(|entry| {
+      entry.commit == commit && entry.day == day && entry.part == part && entry.checksum == checksum
+    })
+  };
+
+  let mut keys = history
+    .iter()
+    .filter(|entry| entry.commit == current)
+    .map(|entry| (entry.day, entry.part.clone(), entry.checksum.clone()))
+    .collect::<Vec<(u8, String, String)>>();
+  keys.sort();
+  keys.dedup();
+
+  let mut comparisons = vec![];
+  for (day, part, checksum) in keys {
+    let (Some(now), Some(then)) = (
+      latest(&current, day, &part, &checksum),
+      latest(&baseline, day, &part, &checksum),
+    ) else {
+      continue;
+    };
+
+    for (phase, baseline, current) in [
+      ("parse", then.parse_ns, now.parse_ns),
+      ("solve", then.solve_ns, now.solve_ns),
+    ] {
+      comparisons.push(Comparison {
+        day,
+        part: part.clone(),
+        phase,
+        baseline: Duration::from_nanos(baseline),
+        current: Duration::from_nanos(current),
+      });
+    }
+  }
+
+  Ok((baseline, current, comparisons))
+}
+
+/// Prints every comparison, returns whether none got more than `threshold`
+/// percent slower.
+pub fn print_table(
+  baseline: &str,
+  current: &str,
+  comparisons: &[Comparison],
+  threshold: u32,
+) -> bool {
+  println!("Comparing {} against {}\n", current, baseline);
+  println!(
+    "{:>3}  {:<4}  {:<5}  {:>10}  {:>10}  {:>8}",
+    "Day", "Part", "Phase", "Baseline", "Current", "Change"
+  );
+
+  let mut ok = true;
+  for comparison in comparisons {
+    let change = comparison.change();
+    let text = format!("{:+.1}%", change);
+    let text = if change > threshold as f64 {
+      ok = false;
+      text.red()
+    } else if change < -(threshold as f64) {
+      text.green()
+    } else {
+      text.normal()
+    };
+
+    println!(
+      "{:>3}  {:<4}  {:<5}  {:>10}  {:>10}  {:>8}",
+      comparison.day,
+      comparison.part,
+      comparison.phase,
+      format_duration(comparison.baseline),
+      format_duration(comparison.current),
+      text
+    );
+  }
+
+  if comparisons.is_empty() {
+    println!("\nNo day was benchmarked on the same input at both commits");
+  } else if !ok {
+    println!("\nSlower than the {}% threshold", threshold);
+  }
+
+  ok
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bench::Stats;
+  use crate::day::Part;
+
+  fn entry(commit: &str, day: u8, checksum: &str, solve_ns: u64) -> Entry {
+    Entry {
+      commit: commit.to_string(),
+      checksum: checksum.to_string(),
+      day,
+      part: "b".to_string(),
+      iterations: 10,
+      parse_ns: 100,
+      solve_ns,
+    }
+  }
+
+  #[test]
+  fn test_compare() {
+    let history = vec![
+      entry("aaa", 5, "x", 1000),
+      entry("bbb", 5, "x", 2000),
+      entry("bbb", 11, "y", 500),
+      entry("ccc", 5, "x", 1500),
+      entry("ccc", 11, "z", 500),
+    ];
+
+    let (baseline, current, comparisons) = compare(&history, None).unwrap();
+    assert_eq!((baseline.as_str(), current.as_str()), ("bbb", "ccc"));
+    // day 11 changed input, so only day 5 compares
+    assert_eq!(comparisons.len(), 2);
+    assert_eq!(comparisons[1].phase, "solve");
+    assert_eq!(comparisons[1].change().round(), -25.0);
+
+    let (_, _, comparisons) = compare(&history, Some("aa")).unwrap();
+    assert_eq!(comparisons[1].change().round(), 50.0);
+
+    assert!(compare(&history, Some("ddd")).is_err());
+    assert!(compare(&history[..1], None).is_err());
+    assert!(compare(&[], None).is_err());
+  }
+
+  #[test]
+  fn test_entries_skip_overrides() {
+    let stats = || Stats::new(&[Duration::from_nanos(100)]).unwrap();
+    let bench = |overridden| Bench {
+      day: 11,
+      part: Part::B,
+      checksum: "x".to_string(),
+      overridden,
+      iterations: 1,
+      parse: stats(),
+      solve: stats(),
+    };
+
+    let entries = entries("aaa", &[bench(false), bench(true)]);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].solve_ns, 100);
+  }
+
+  #[test]
+  fn test_history() {
+    let path = std::env::temp_dir().join(format!("perf_history_{}.jsonl", std::process::id()));
+    let entries = vec![entry("aaa", 5, "x", 1000), entry("aaa", 11, "y", 500)];
+
+    append(&path, &[]).unwrap();
+    assert!(!path.exists());
+
+    append(&path, &entries[..1]).unwrap();
+    append(&path, &entries[1..]).unwrap();
+    let loaded = load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, Ok(entries));
+    assert_eq!(load(&path), Ok(vec![]));
+  }
+}
@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::runner::Run;
+
+/// How the results of a run are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  /// Free text for one day, a table for several.
+  Text,
+  /// A JSON array with one object per day and part.
+  Json,
+  /// A header line and one row per day and part.
+  Csv,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Format, String> {
+    match s {
+      "text" => Ok(Format::Text),
+      "json" => Ok(Format::Json),
+      "csv" => Ok(Format::Csv),
+      other => Err(format!("Unknown format: {}", other)),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct Record {
+  day: u8,
+  part: String,
+  answer: Option<String>,
+  /// Time spent reading the input in nanoseconds, missing when it was never
+  /// read.
+  load_ns: Option<u128>,
+  /// Parse time of the day in nanoseconds, the same for both parts as they
+  /// share one parse. Missing when the input was never parsed.
+  parse_ns: Option<u128>,
+  /// Solve time in nanoseconds, missing when the solver never ran.
+  time_ns: Option<u128>,
+  /// Peak heap bytes and allocations of the parse and of the solve, missing
+  /// without the counting allocator or when the phase never finished.
+  parse_peak_bytes: Option<usize>,
+  parse_allocations: Option<usize>,
+  peak_bytes: Option<usize>,
+  allocations: Option<usize>,
+  checksum: Option<String>,
+  status: &'static str,
+  error: Option<String>,
+}
+
+impl Record {
+  fn new(run: &Run) -> Record {
+    let ran = run.answer.is_some() || run.error.is_some();
+
+    Record {
+      day: run.day,
+      part: run.part.to_string(),
+      answer: run.answer.as_ref().map(|answer| answer.to_string()),
+      load_ns: (!run.load_elapsed.is_zero()).then_some(run.load_elapsed.as_nanos()),
+      parse_ns: (!run.parse_elapsed.is_zero()).then_some(run.parse_elapsed.as_nanos()),
+      time_ns: ran.then_some(run.elapsed.as_nanos()),
+      parse_peak_bytes: run.parse_memory.map(|memory| memory.peak),
+      parse_allocations: run.parse_memory.map(|memory| memory.allocations),
+      peak_bytes: run.memory.map(|memory| memory.peak),
+      allocations: run.memory.map(|memory| memory.allocations),
+      checksum: run.checksum.clone(),
+      status: run.status.name(),
+      error: run.error.as_ref().map(|err| err.to_string()),
+    }
+  }
+}
+
+pub fn json(runs: &[Run]) -> String {
+  let records = runs.iter().map(Record::new).collect::<Vec<Record>>();
+
+  serde_json::to_string_pretty(&records).expect("records always serialize")
+}
+
+pub fn csv(runs: &[Run]) -> String {
+  let mut out = "day,part,answer,load_ns,parse_ns,time_ns,parse_peak_bytes,parse_allocations,\
+     peak_bytes,allocations,checksum,status,error\n"
+    .to_string();
+
+  for record in runs.iter().map(Record::new) {
+    let fields = [
+      record.day.to_string(),
+      record.part,
+      record.answer.unwrap_or_default(),
+      optional(record.load_ns),
+      optional(record.parse_ns),
+      optional(record.time_ns),
+      optional(record.parse_peak_bytes),
+      optional(record.parse_allocations),
+      optional(record.peak_bytes),
+      optional(record.allocations),
+      record.checksum.unwrap_or_default(),
+      record.status.to_string(),
+      record.error.unwrap_or_default(),
+    ];
+
+    let line = fields
+      .iter()
+      .map(|field| csv_field(field))
+      .collect::<Vec<String>>()
+      .join(",");
+    out.push_str(&line);
+    out.push('\n');
+  }
+
+  out
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+  value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Quotes a field when it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+  use crate::alloc::Usage;
+  use crate::day::Part;
+  use crate::runner::Status;
+
+  fn runs() -> Vec<Run> {
+    let mut solved = Run::skipped(9, Part::A, Status::Pass);
+    solved.answer = Some((-10).into());
+    solved.load_elapsed = Duration::from_micros(40);
+    solved.parse_elapsed = Duration::from_micros(3);
+    solved.elapsed = Duration::from_micros(12);
+    solved.checksum = Some("ab12".to_string());
+    solved.memory = Some(Usage {
+      peak: 2048,
+      allocations: 7,
+    });
+
+    vec![solved, Run::skipped(10, Part::B, Status::Unsolved)]
+  }
+
+  #[test]
+  fn test_json() {
+    let json: serde_json::Value = serde_json::from_str(&json(&runs())).unwrap();
+
+    assert_eq!(json[0]["answer"], "-10");
+    assert_eq!(json[0]["load_ns"], 40000);
+    assert_eq!(json[0]["parse_ns"], 3000);
+    assert_eq!(json[0]["time_ns"], 12000);
+    assert_eq!(json[0]["peak_bytes"], 2048);
+    assert!(json[0]["parse_peak_bytes"].is_null());
+    assert_eq!(json[0]["checksum"], "ab12");
+    assert_eq!(json[0]["status"], "pass");
+    assert_eq!(json[1]["part"], "b");
+    assert!(json[1]["time_ns"].is_null());
+  }
+
+  #[test]
+  fn test_csv() {
+    assert_eq!(
+      csv(&runs()),
+      "day,part,answer,load_ns,parse_ns,time_ns,parse_peak_bytes,parse_allocations,\
+       peak_bytes,allocations,checksum,status,error\n\
+       9,a,-10,40000,3000,12000,,,2048,7,ab12,pass,\n\
+       10,b,,,,,,,,,,unsolved,\n"
+    );
+    assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+  }
+}
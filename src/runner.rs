@@ -0,0 +1,687 @@
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use colored::Colorize;
+
+use crate::alloc::{self, Usage};
+use crate::answer::Answer;
+use crate::answers::{Answers, ANSWERS_FILE};
+use crate::day::{Part, Progress, Puzzle};
+use crate::error::{Error, Result};
+use crate::input;
+use crate::params::Params;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+  Pass,
+  Fail,
+  Unknown,
+  NoInput,
+  Unsolved,
+  Error,
+  Timeout,
+}
+
+#[derive(Debug)]
+pub struct Run {
+  pub day: u8,
+  pub part: Part,
+  pub answer: Option<Answer>,
+  /// Time spent reading the input, shared by all parts of the day.
+  pub load_elapsed: Duration,
+  /// Time spent parsing the input, shared by all parts of the day.
+  pub parse_elapsed: Duration,
+  /// Heap used parsing the input, shared by all parts of the day. Only known
+  /// with the counting allocator.
+  pub parse_memory: Option<Usage>,
+  /// Time spent solving this part.
+  pub elapsed: Duration,
+  /// Heap used solving this part.
+  pub memory: Option<Usage>,
+  pub status: Status,
+  pub error: Option<Error>,
+  /// `input::checksum` of the input the part ran on.
+  pub checksum: Option<String>,
+  /// The confirmed answer from the answers file, if there is one.
+  pub expected: Option<String>,
+}
+
+impl Status {
+  /// Lowercase name for machine-readable output.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Status::Pass => "pass",
+      Status::Fail => "fail",
+      Status::Unknown => "unknown",
+      Status::NoInput => "no_input",
+      Status::Unsolved => "unsolved",
+      Status::Error => "error",
+      Status::Timeout => "timeout",
+    }
+  }
+}
+
+/// Parses `all`, a single day (`7`) or a range of days into the matching
+/// registered days, in calendar order. Both `3..7` and `3..=7` are days 3
+/// through 7.
+pub fn select_days(
+  arg: &str,
+  registry: &[&'static dyn Puzzle],
+) -> std::result::Result<Vec<&'static dyn Puzzle>, String> {
+  let parse_day = |s: &str| s.parse::<u8>().map_err(|_| format!("Invalid day: {}", s));
+
+  let (first, last) = if arg == "all" {
+    (u8::MIN, u8::MAX)
+  } else if let Some((first, last)) = arg.split_once("..") {
+    let last = last.strip_prefix('=').unwrap_or(last);
+    (parse_day(first)?, parse_day(last)?)
+  } else {
+    let day = parse_day(arg)?;
+    (day, day)
+  };
+
+  let mut days = registry
+    .iter()
+    .filter(|puzzle| (first..=last).contains(&puzzle.number()))
+    .copied()
+    .collect::<Vec<&'static dyn Puzzle>>();
+  days.sort_by_key(|puzzle| puzzle.number());
+
+  if days.is_empty() {
+    return Err(format!("No days registered for {}", arg));
+  }
+
+  Ok(days)
+}
+
+impl Run {
+  /// A run that never reached the solver.
+  pub fn skipped(day: u8, part: Part, status: Status) -> Run {
+    Run {
+      day,
+      part,
+      answer: None,
+      load_elapsed: Duration::ZERO,
+      parse_elapsed: Duration::ZERO,
+      parse_memory: None,
+      elapsed: Duration::ZERO,
+      memory: None,
+      status,
+      error: None,
+      checksum: None,
+      expected: None,
+    }
+  }
+}
+
+/// Runs `parts` of one day on a single parse of `input`. Parts the day has
+/// not solved come back as `Status::Unsolved`, a panic is caught and fails
+/// the part it happened in, or every part when it was the parse.
+///
+/// With a `timeout` the day runs on its own thread, and a part that has not
+/// finished within `timeout` of the previous phase is reported as
+/// `Status::Timeout`. The parts after it start over on a new thread, with a
+/// new parse. Rust cannot stop a thread, so the stuck solver is left running
+/// in the background until the process exits.
+pub fn run(
+  puzzle: &'static dyn Puzzle,
+  parts: &[Part],
+  input: &str,
+  params: &Params,
+  answers: &Answers,
+  timeout: Option<Duration>,
+) -> Vec<Run> {
+  let checksum = input::checksum(input);
+  let solvable = parts
+    .iter()
+    .copied()
+    .filter(|part| puzzle.parts().contains(part))
+    .collect::<Vec<Part>>();
+
+  let mut runs = parts
+    .iter()
+    .map(|part| {
+      let mut run = Run::skipped(puzzle.number(), *part, Status::Unsolved);
+      run.checksum = Some(checksum.clone());
+      run
+    })
+    .collect::<Vec<Run>>();
+
+  if solvable.is_empty() {
+    return runs;
+  }
+
+  let mut remaining = solvable;
+  // the first parse, a day that starts over after a timeout parses again
+  let mut parse = None;
+
+  'attempts: while !remaining.is_empty() {
+    let (sender, receiver) = mpsc::channel();
+    match timeout {
+      Some(_) => {
+        let (parts, input, params) = (remaining.clone(), input.to_string(), params.clone());
+        thread::spawn(move || solve(puzzle, &parts, &input, &params, sender));
+      }
+      None => solve(puzzle, &remaining, input, params, sender),
+    }
+
+    let mut parsed = false;
+    loop {
+      let event = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+      };
+
+      match event {
+        Ok(Event::Progress(Progress::Parsed(elapsed, memory))) => {
+          parsed = true;
+          parse.get_or_insert((elapsed, memory));
+        }
+        Ok(Event::Progress(Progress::Solved(part, result, elapsed, memory))) => {
+          let run = runs.iter_mut().find(|run| run.part == part).unwrap();
+          (run.parse_elapsed, run.parse_memory) = parse.unwrap_or_default();
+          run.elapsed = elapsed;
+          run.memory = memory;
+          record(run, result, params, answers);
+          remaining.retain(|remaining| *remaining != part);
+        }
+        Ok(Event::Finished(Ok(()))) | Err(RecvTimeoutError::Disconnected) => break 'attempts,
+        Ok(Event::Finished(Err(err))) => {
+          fail(&mut runs, &remaining, parse, Status::Error, err);
+          break 'attempts;
+        }
+        Err(RecvTimeoutError::Timeout) => {
+          let err = Error::Timeout {
+            day: puzzle.number(),
+            limit: timeout.unwrap(),
+          };
+
+          // a parse that hangs would hang again, no part gets to run
+          if !parsed {
+            fail(&mut runs, &remaining, parse, Status::Timeout, err);
+            break 'attempts;
+          }
+
+          fail(&mut runs, &remaining[..1], parse, Status::Timeout, err);
+          remaining.remove(0);
+          continue 'attempts;
+        }
+      }
+    }
+  }
+
+  runs
+}
+
+/// Fails the runs of `parts` with `err`.
+fn fail(
+  runs: &mut [Run],
+  parts: &[Part],
+  parse: Option<(Duration, Option<Usage>)>,
+  status: Status,
+  err: Error,
+) {
+  for run in runs.iter_mut().filter(|run| parts.contains(&run.part)) {
+    (run.parse_elapsed, run.parse_memory) = parse.unwrap_or_default();
+    run.status = status;
+    run.error = Some(err.clone());
+  }
+}
+
+/// Forgets the heap use the process-wide counters cannot pin on one day: all
+/// of it when the days ran `parallel`, otherwise everything from the first
+/// timeout on, as the abandoned solver keeps allocating.
+pub fn discard_shared_memory(runs: &mut [Run], parallel: bool) {
+  let first = match parallel {
+    true => 0,
+    false => runs
+      .iter()
+      .position(|run| run.status == Status::Timeout)
+      .unwrap_or(runs.len()),
+  };
+
+  for run in &mut runs[first..] {
+    run.parse_memory = None;
+    run.memory = None;
+  }
+}
+
+thread_local! {
+  /// Whether this thread is inside `solve`, which reports its panics itself.
+  static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook that stays quiet about the panics `solve` catches,
+/// so they only show up as `Error::Panic` and not as backtraces in the middle
+/// of the output. Any other panic still goes to the previous hook.
+pub fn quiet_solver_panics() {
+  let previous = panic::take_hook();
+  panic::set_hook(Box::new(move |info| {
+    if !SOLVING.get() {
+      previous(info);
+    }
+  }));
+}
+
+enum Event {
+  Progress(Progress),
+  Finished(Result<()>),
+}
+
+/// Solves `parts`, sending each phase to `sender` as it finishes, and
+/// whatever ended the day last.
+fn solve(puzzle: &dyn Puzzle, parts: &[Part], input: &str, params: &Params, sender: Sender<Event>) {
+  let mut report = |progress| {
+    // the receiver is gone once the day timed out
+    let _ = sender.send(Event::Progress(progress));
+  };
+
+  SOLVING.set(true);
+  let result = panic::catch_unwind(AssertUnwindSafe(|| {
+    puzzle.solve_parts(parts, input, params, &mut report)
+  }));
+  SOLVING.set(false);
+
+  // the parts catch their own panics, this is one while parsing
+  let result =
+    result.unwrap_or_else(|payload| Err(Error::panic(puzzle.number(), payload.as_ref())));
+
+  let _ = sender.send(Event::Finished(result));
+}
+
+/// Fills in the answer of `run` and checks it against the known answers.
+fn record(run: &mut Run, result: Result<Answer>, params: &Params, answers: &Answers) {
+  let answer = match result {
+    Ok(answer) => answer,
+    Err(err) => {
+      run.status = Status::Error;
+      run.error = Some(err);
+      return;
+    }
+  };
+
+  run.expected = answers
+    .get(run.day, run.part, run.checksum.as_deref())
+    .filter(|_| !params.overridden())
+    .map(|known| known.to_string());
+  run.status = match &run.expected {
+    Some(known) if *known == answer.to_string() => Status::Pass,
+    Some(_) => Status::Fail,
+    None => Status::Unknown,
+  };
+  run.answer = Some(answer);
+}
+
+pub fn format_duration(duration: Duration) -> String {
+  let secs = duration.as_secs_f64();
+
+  if secs >= 1.0 {
+    format!("{:.2}s", secs)
+  } else if secs >= 1e-3 {
+    format!("{:.2}ms", secs * 1e3)
+  } else {
+    format!("{:.2}µs", secs * 1e6)
+  }
+}
+
+/// Whether `runs[index]` is the first run of its day, the one that reports
+/// the shared load and parse times.
+fn first_of_day(runs: &[Run], index: usize) -> bool {
+  index == 0 || runs[index - 1].day != runs[index].day
+}
+
+/// Load and parse time of every day once, plus the solve time of every part.
+pub fn total_time(runs: &[Run]) -> Duration {
+  (0..runs.len())
+    .filter(|index| first_of_day(runs, *index))
+    .map(|index| runs[index].load_elapsed + runs[index].parse_elapsed)
+    .chain(runs.iter().map(|run| run.elapsed))
+    .sum()
+}
+
+pub fn print_table(runs: &[Run]) {
+  println!(
+    "{:>3}  {:<4}  {:>20}  {:>10}  {:>10}  {:>10}  {:>10}  {:>8}  Status",
+    "Day", "Part", "Answer", "Load", "Parse", "Time", "Peak", "Allocs"
+  );
+
+  for (index, run) in runs.iter().enumerate() {
+    let answer = match &run.answer {
+      Some(answer) => answer.to_string(),
+      None => "-".to_string(),
+    };
+    let shared = |elapsed: Duration| match elapsed.is_zero() {
+      false if first_of_day(runs, index) => format_duration(elapsed),
+      _ => "".to_string(),
+    };
+    let time = match run.answer {
+      Some(_) => format_duration(run.elapsed),
+      None => "-".to_string(),
+    };
+    let (peak, allocations) = match (&run.answer, run.memory) {
+      (Some(_), Some(memory)) => (
+        alloc::format_bytes(memory.peak),
+        memory.allocations.to_string(),
+      ),
+      _ => ("-".to_string(), "-".to_string()),
+    };
+    let status = match run.status {
+      Status::Pass => "PASS".green(),
+      Status::Fail => "FAIL".red(),
+      Status::Unknown => "?".normal(),
+      Status::NoInput => "NO INPUT".yellow(),
+      Status::Unsolved => "UNSOLVED".dimmed(),
+      Status::Error => "ERROR".red(),
+      Status::Timeout => "TIMEOUT".red(),
+    };
+
+    println!(
+      "{:>3}  {:<4}  {:>20}  {:>10}  {:>10}  {:>10}  {:>10}  {:>8}  {}",
+      run.day,
+      run.part,
+      answer,
+      shared(run.load_elapsed),
+      shared(run.parse_elapsed),
+      time,
+      peak,
+      allocations,
+      status
+    );
+  }
+
+  let passed = runs.iter().filter(|run| run.status == Status::Pass).count();
+  let failed = runs.iter().filter(|run| run.status == Status::Fail).count();
+
+  println!(
+    "\n{} passed, {} failed, total time {}",
+    passed,
+    failed,
+    format_duration(total_time(runs))
+  );
+
+  // a parse error is shared by all parts of the day, print it once
+  let mut errors = runs
+    .iter()
+    .filter_map(|run| run.error.as_ref())
+    .collect::<Vec<&Error>>();
+  errors.dedup();
+  for err in errors {
+    eprintln!("\n{}", err);
+  }
+}
+
+/// Reports the parts whose answer no longer matches the answers file, and
+/// prints the unconfirmed ones as entries ready to paste into it. Returns
+/// whether every confirmed answer still matches.
+pub fn print_verify(runs: &[Run]) -> bool {
+  let mut ok = true;
+
+  for run in runs {
+    match (run.status, &run.answer, &run.expected) {
+      (Status::Fail, Some(answer), Some(expected)) => {
+        ok = false;
+        let message = format!("expected {}, got {}", expected, answer);
+        eprintln!("day {} {}: {}", run.day, run.part, message.red());
+      }
+      (Status::Error | Status::Timeout, _, _) => ok = false,
+      _ => {}
+    }
+  }
+
+  let unconfirmed = runs
+    .iter()
+    .filter(|run| run.status == Status::Unknown)
+    .collect::<Vec<&Run>>();
+
+  if !unconfirmed.is_empty() {
+    println!("\nNot confirmed in {} yet:", ANSWERS_FILE);
+  }
+  for run in unconfirmed {
+    if let (Some(answer), Some(checksum)) = (&run.answer, &run.checksum) {
+      println!(
+        "\n[[answer]]\nday = {}\npart = \"{}\"\nvalue = \"{}\"\ninput = \"{}\"",
+        run.day, run.part, answer, checksum
+      );
+    }
+  }
+
+  ok
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+  use super::*;
+  use crate::day::Day;
+
+  struct Fake<const N: u8>;
+
+  impl<const N: u8> Day for Fake<N> {
+    type Input = ();
+
+    const NUMBER: u8 = N;
+    const TITLE: &'static str = "Fake";
+
+    fn parse(_: &str) -> Result<()> {
+      Ok(())
+    }
+
+    fn part_a(_: &(), _: &Params) -> Result<Answer> {
+      Ok(0.into())
+    }
+
+    fn part_b(_: &(), _: &Params) -> Result<Answer> {
+      Ok(0.into())
+    }
+  }
+
+  static PARSES: AtomicUsize = AtomicUsize::new(0);
+
+  /// Counts its parses, and fails to parse anything but digits.
+  struct Counted;
+
+  impl Day for Counted {
+    type Input = u64;
+
+    const NUMBER: u8 = 9;
+    const TITLE: &'static str = "Counted";
+
+    fn parse(input: &str) -> Result<u64> {
+      PARSES.fetch_add(1, AtomicOrdering::SeqCst);
+      crate::error::parse_number(9, 0, input, input)
+    }
+
+    fn part_a(input: &u64, _: &Params) -> Result<Answer> {
+      Ok((*input).into())
+    }
+
+    fn part_b(input: &u64, _: &Params) -> Result<Answer> {
+      Ok((input * 2).into())
+    }
+  }
+
+  struct Panics;
+
+  impl Day for Panics {
+    type Input = ();
+
+    const NUMBER: u8 = 10;
+    const TITLE: &'static str = "Panics";
+
+    fn parse(_: &str) -> Result<()> {
+      Ok(())
+    }
+
+    fn part_a(_: &(), _: &Params) -> Result<Answer> {
+      panic!("part a is broken")
+    }
+
+    fn part_b(_: &(), _: &Params) -> Result<Answer> {
+      Ok(0.into())
+    }
+  }
+
+  /// Takes far longer than any test timeout on part a.
+  struct Slow;
+
+  impl Day for Slow {
+    type Input = ();
+
+    const NUMBER: u8 = 8;
+    const TITLE: &'static str = "Slow";
+
+    fn parse(_: &str) -> Result<()> {
+      Ok(())
+    }
+
+    fn part_a(_: &(), _: &Params) -> Result<Answer> {
+      thread::sleep(Duration::from_secs(5));
+      Ok(0.into())
+    }
+
+    fn part_b(_: &(), _: &Params) -> Result<Answer> {
+      Ok(0.into())
+    }
+  }
+
+  fn numbers(arg: &str) -> Vec<u8> {
+    let registry: Vec<&'static dyn Puzzle> = vec![&Fake::<4>, &Fake::<1>, &Fake::<2>, &Fake::<3>];
+
+    select_days(arg, &registry)
+      .unwrap()
+      .iter()
+      .map(|puzzle| puzzle.number())
+      .collect()
+  }
+
+  #[test]
+  fn test_select_days() {
+    assert_eq!(numbers("all"), vec![1, 2, 3, 4]);
+    assert_eq!(numbers("3"), vec![3]);
+    assert_eq!(numbers("2..4"), vec![2, 3, 4]);
+    assert_eq!(numbers("2..=4"), vec![2, 3, 4]);
+  }
+
+  #[test]
+  fn test_select_days_invalid() {
+    let registry: Vec<&'static dyn Puzzle> = vec![&Fake::<1>];
+
+    assert!(select_days("7", &registry).is_err());
+    assert!(select_days("x..3", &registry).is_err());
+    assert!(select_days("1..=x", &registry).is_err());
+  }
+
+  #[test]
+  fn test_run_parses_once() {
+    let params = Params::new(9, &[], &[]);
+    let runs = run(
+      &Counted,
+      &[Part::A, Part::B],
+      "21",
+      &params,
+      &Answers::default(),
+      None,
+    );
+
+    assert_eq!(PARSES.load(AtomicOrdering::SeqCst), 1);
+    assert_eq!(runs[0].answer, Some(21.into()));
+    assert_eq!(runs[1].answer, Some(42.into()));
+    assert_eq!(runs[0].parse_elapsed, runs[1].parse_elapsed);
+
+    let runs = run(
+      &Counted,
+      &[Part::A, Part::B],
+      "2x",
+      &params,
+      &Answers::default(),
+      None,
+    );
+    assert!(runs.iter().all(|run| run.status == Status::Error));
+    assert_eq!(runs[0].error, runs[1].error);
+  }
+
+  #[test]
+  fn test_run_catches_panic() {
+    let params = Params::new(10, &[], &[]);
+    let runs = run(
+      &Panics,
+      &[Part::A, Part::B],
+      "",
+      &params,
+      &Answers::default(),
+      None,
+    );
+
+    assert_eq!(runs[0].status, Status::Error);
+    assert_eq!(
+      runs[0].error.as_ref().unwrap().to_string(),
+      "day 10: panicked: part a is broken"
+    );
+    // part b still runs after part a panicked
+    assert_eq!(runs[1].answer, Some(0.into()));
+    // panics after the day are not solver panics, the hook reports them
+    assert!(!SOLVING.get());
+  }
+
+  #[test]
+  fn test_run_timeout() {
+    let params = Params::new(8, &[], &[]);
+    let timeout = Some(Duration::from_millis(50));
+
+    let runs = run(
+      &Slow,
+      &[Part::B, Part::A],
+      "",
+      &params,
+      &Answers::default(),
+      timeout,
+    );
+    assert_eq!(runs[0].answer, Some(0.into()));
+    assert_eq!(runs[1].status, Status::Timeout);
+    assert_eq!(
+      runs[1].error.as_ref().unwrap().to_string(),
+      "day 8: timed out after 0.05s"
+    );
+
+    // part a hangs, part b starts over without it
+    let runs = run(
+      &Slow,
+      &[Part::A, Part::B],
+      "",
+      &params,
+      &Answers::default(),
+      timeout,
+    );
+    assert_eq!(runs[0].status, Status::Timeout);
+    assert_eq!(runs[1].answer, Some(0.into()));
+  }
+
+  #[test]
+  fn test_discard_shared_memory() {
+    let usage = Some(Usage {
+      peak: 64,
+      allocations: 1,
+    });
+    let runs = || {
+      [Status::Pass, Status::Timeout, Status::Pass].map(|status| {
+        let mut run = Run::skipped(1, Part::A, status);
+        (run.parse_memory, run.memory) = (usage, usage);
+        run
+      })
+    };
+
+    let mut sequential = runs();
+    discard_shared_memory(&mut sequential, false);
+    assert_eq!(sequential.map(|run| run.memory), [usage, None, None]);
+
+    let mut parallel = runs();
+    discard_shared_memory(&mut parallel, true);
+    assert!(parallel
+      .iter()
+      .all(|run| run.memory.is_none() && run.parse_memory.is_none()));
+  }
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::input;
+
+/// The module a new day starts from, `{day}` is replaced by its number.
+const TEMPLATE: &str = r#"//! Day {day}: what the puzzle is about.
+
+use crate::answer::Answer;
+use crate::day::{Day, Part};
+use crate::error::{Error, Result};
+use crate::params::Params;
+
+/// One string per line, change it to whatever the puzzle needs.
+pub fn parse_input(input: &str) -> Result<Vec<String>> {
+  Ok(input.lines().map(|line| line.to_string()).collect())
+}
+
+/// What part a asks for.
+pub fn solve_a(_lines: &[String]) -> Result<Answer> {
+  Err(Error::unsolvable(Day{day}::NUMBER, "not solved yet"))
+}
+
+/// What part b asks for.
+pub fn solve_b(_lines: &[String]) -> Result<Answer> {
+  Err(Error::unsolvable(Day{day}::NUMBER, "not solved yet"))
+}
+
+/// The puzzle, see `Day`.
+pub struct Day{day};
+
+impl Day for Day{day} {
+  type Input = Vec<String>;
+
+  const NUMBER: u8 = {day};
+  const TITLE: &'static str = "Day {day}";
+  // add each part once it is solved
+  const PARTS: &'static [Part] = &[];
+
+  fn parse(input: &str) -> Result<Vec<String>> {
+    parse_input(input)
+  }
+
+  fn part_a(lines: &Vec<String>, _: &Params) -> Result<Answer> {
+    solve_a(lines)
+  }
+
+  fn part_b(lines: &Vec<String>, _: &Params) -> Result<Answer> {
+    solve_b(lines)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::input::normalize;
+
+  const EXAMPLE: &str = include_str!("examples/day{day}.txt");
+
+  #[test]
+  #[ignore = "needs the example in src/examples/day{day}.txt and its answer"]
+  fn test_solve_a() {
+    assert_eq!(solve_a(&parse_input(&normalize(EXAMPLE)).unwrap()), Ok(0.into()));
+  }
+
+  #[test]
+  #[ignore = "needs the example in src/examples/day{day}.txt and its answer"]
+  fn test_solve_b() {
+    assert_eq!(solve_b(&parse_input(&normalize(EXAMPLE)).unwrap()), Ok(0.into()));
+  }
+}
+"#;
+
+pub fn render(day: u8) -> String {
+  TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/// Adds `dayN::DayN` to the `days!` list of `lib`, keeping it in calendar
+/// order.
+pub fn register(lib: &str, day: u8) -> std::result::Result<String, String> {
+  let lines = lib.lines().collect::<Vec<&str>>();
+  let start = lines
+    .iter()
+    .position(|line| *line == "days! {")
+    .ok_or("no days! list")?;
+  let end = start
+    + lines[start..]
+      .iter()
+      .position(|line| *line == "}")
+      .ok_or("days! list is not closed")?;
+
+  let registered = |line: &str| {
+    line
+      .trim()
+      .strip_prefix("day")
+      .and_then(|line| line.split_once("::"))
+      .and_then(|(number, _)| number.parse::<u8>().ok())
+  };
+
+  let mut at = end;
+  for (index, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+    match registered(line) {
+      Some(number) if number == day => return Err(format!("day {} is already registered", day)),
+      Some(number) if number > day => {
+        at = index;
+        break;
+      }
+      _ => {}
+    }
+  }
+
+  let entry = format!("  day{}::Day{},", day, day);
+  let mut out = lines[..at].to_vec();
+  out.push(&entry);
+  out.extend(&lines[at..]);
+
+  Ok(out.join("\n") + "\n")
+}
+
+/// Writes the module, an empty input and an empty example for `day` under
+/// `src`, and registers it in `src/lib.rs`. Nothing is overwritten. Returns
+/// the files written.
+pub fn create(src: &Path, day: u8) -> Result<Vec<PathBuf>> {
+  let module = src.join(format!("day{}.rs", day));
+  let example = src.join("examples").join(format!("day{}.txt", day));
+  let input = input::day_path(day);
+  let lib = src.join("lib.rs");
+
+  let io_error = |path: &Path, message: String| Error::Io {
+    path: path.to_path_buf(),
+    message,
+  };
+
+  for path in [&module, &example, &input] {
+    if path.exists() {
+      return Err(io_error(path, "already exists".to_string()));
+    }
+  }
+
+  let registered = fs::read_to_string(&lib)
+    .map_err(|err| io_error(&lib, err.to_string()))
+    .and_then(|content| register(&content, day).map_err(|message| io_error(&lib, message)))?;
+
+  for (path, content) in [
+    (&module, render(day)),
+    (&example, String::new()),
+    (&input, String::new()),
+  ] {
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir).map_err(|err| io_error(dir, err.to_string()))?;
+    }
+    fs::write(path, content).map_err(|err| io_error(path, err.to_string()))?;
+  }
+  fs::write(&lib, registered).map_err(|err| io_error(&lib, err.to_string()))?;
+
+  Ok(vec![module, example, input, lib])
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const LIB: &str = "\
+pub mod answer;
+
+days! {
+  day1::Day1,
+  day3::Day3,
+}
+";
+
+  #[test]
+  fn test_render() {
+    let module = render(12);
+
+    assert!(module.contains("pub struct Day12;"));
+    assert!(module.contains("const NUMBER: u8 = 12;"));
+    assert!(module.contains("include_str!(\"examples/day12.txt\")"));
+    assert!(!module.contains("{day}"));
+  }
+
+  #[test]
+  fn test_register() {
+    assert_eq!(
+      register(LIB, 2).unwrap(),
+      LIB.replace("  day3", "  day2::Day2,\n  day3")
+    );
+    assert_eq!(
+      register(LIB, 12).unwrap(),
+      LIB.replace("}", "  day12::Day12,\n}")
+    );
+    assert!(register(LIB, 3).is_err());
+    assert!(register("pub mod answer;\n", 3).is_err());
+  }
+}
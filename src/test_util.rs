@@ -0,0 +1,25 @@
+/// A seeded xorshift generator, so randomized tests see the same numbers on
+/// every run.
+pub struct XorShift {
+  state: u64,
+}
+
+impl XorShift {
+  /// `seed` must not be 0, xorshift never leaves that state.
+  pub fn new(seed: u64) -> XorShift {
+    assert_ne!(seed, 0, "xorshift needs a non-zero seed");
+    XorShift { state: seed }
+  }
+
+  pub fn next(&mut self) -> u64 {
+    self.state ^= self.state << 13;
+    self.state ^= self.state >> 7;
+    self.state ^= self.state << 17;
+    self.state
+  }
+
+  /// A number in `0..bound`.
+  pub fn below(&mut self, bound: u64) -> u64 {
+    self.next() % bound
+  }
+}
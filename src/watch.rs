@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use colored::Colorize;
+
+use crate::input::Source;
+use crate::runner::{format_duration, Run};
+
+/// How often the watched files are checked for changes.
+pub const POLL: Duration = Duration::from_millis(500);
+
+/// A file `watch` keeps an eye on, e.g. the input or the example of a day.
+pub struct Watched {
+  pub label: &'static str,
+  pub path: PathBuf,
+  modified: Option<SystemTime>,
+  previous: Option<Vec<Run>>,
+}
+
+impl Watched {
+  pub fn new(label: &'static str, path: PathBuf) -> Watched {
+    Watched {
+      label,
+      path,
+      modified: None,
+      previous: None,
+    }
+  }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Solves `day` on each of `files` with `solve`, then again on every file that
+/// changes, until the process is stopped. Each answer and time is shown next
+/// to the one from the previous run of the same file.
+pub fn watch(day: u8, files: &mut [Watched], solve: impl Fn(&str) -> Vec<Run>) -> ! {
+  let mut first = true;
+
+  loop {
+    for file in files.iter_mut() {
+      let stamp = modified(&file.path);
+      if !first && stamp == file.modified {
+        continue;
+      }
+      file.modified = stamp;
+
+      println!("\n{} {}", file.label.bold(), file.path.display());
+      let input = match Source::File(file.path.clone()).read(day) {
+        Ok(input) if !input.is_empty() => input,
+        Ok(_) => {
+          println!("  empty, waiting for it to be filled in");
+          file.previous = None;
+          continue;
+        }
+        Err(err) => {
+          println!("  {}", err);
+          file.previous = None;
+          continue;
+        }
+      };
+
+      let runs = solve(&input);
+      for run in &runs {
+        let previous = file
+          .previous
+          .iter()
+          .flatten()
+          .find(|previous| previous.part == run.part);
+        println!("  {}", describe(run, previous));
+      }
+      file.previous = Some(runs);
+    }
+
+    first = false;
+    thread::sleep(POLL);
+  }
+}
+
+/// One line per part: the answer and time, and how they changed since
+/// `previous`.
+pub fn describe(run: &Run, previous: Option<&Run>) -> String {
+  let show = |run: &Run| match (&run.answer, &run.error) {
+    (Some(answer), _) => answer.to_string(),
+    (None, Some(err)) => err.to_string().lines().next().unwrap_or("").to_string(),
+    (None, None) => run.status.name().to_string(),
+  };
+
+  let answer = show(run);
+  let mut line = format!("{}: {}", run.part, answer);
+
+  let Some(previous) = previous else {
+    if run.answer.is_some() {
+      line.push_str(&format!("  {}", format_duration(run.elapsed)));
+    }
+    return line;
+  };
+
+  let was = show(previous);
+  if was != answer {
+    line.push_str(&format!(" {}", format!("(was {})", was).yellow()));
+  }
+
+  if run.answer.is_some() {
+    line.push_str(&format!("  {}", format_duration(run.elapsed)));
+    if previous.answer.is_some() {
+      let change = match run.elapsed >= previous.elapsed {
+        true => format!("+{}", format_duration(run.elapsed - previous.elapsed)).red(),
+        false => format!("-{}", format_duration(previous.elapsed - run.elapsed)).green(),
+      };
+      line.push_str(&format!(" ({})", change));
+    }
+  }
+
+  line
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day::Part;
+  use crate::runner::Status;
+
+  fn solved(answer: i64, micros: u64) -> Run {
+    let mut run = Run::skipped(7, Part::B, Status::Unknown);
+    run.answer = Some(answer.into());
+    run.elapsed = Duration::from_micros(micros);
+    run
+  }
+
+  #[test]
+  fn test_describe() {
+    colored::control::set_override(false);
+
+    assert_eq!(describe(&solved(5, 10), None), "b: 5  10.00µs");
+    assert_eq!(
+      describe(&solved(5, 10), Some(&solved(5, 12))),
+      "b: 5  10.00µs (-2.00µs)"
+    );
+    assert_eq!(
+      describe(&solved(6, 15), Some(&solved(5, 12))),
+      "b: 6 (was 5)  15.00µs (+3.00µs)"
+    );
+    assert_eq!(
+      describe(
+        &Run::skipped(7, Part::B, Status::Unsolved),
+        Some(&solved(5, 12))
+      ),
+      "b: unsolved (was 5)"
+    );
+  }
+}